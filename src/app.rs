@@ -0,0 +1,542 @@
+//! TUI state machine: key actions in, side effects out
+//!
+//! `App::update` only touches `FuzzyState` and `Mode`; database writes, file creation
+//! and exiting are returned as `Effect`s for `driver::perform` to carry out, from the
+//! terminal loop in `driver::run_tui` or a headless `driver::run_script`.
+
+use std::path::PathBuf;
+
+use crate::fs;
+use crate::input::InputEvent;
+use crate::keymap::{Action, KeymapMode};
+use crate::ui::{FuzzyState, TextInput};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mode {
+    Normal,
+    Search,
+    BookmarkInput(TextInput),
+    BookmarkOverwrite {
+        alias: String,
+        existing_path: String,
+    },
+    BookmarkRemove,
+    /// Confirm forgetting a missing bookmark or remembered directory
+    ForgetEntry,
+    /// New target for a bookmark whose directory is missing
+    BookmarkRepoint(TextInput),
+    CreateEntry(TextInput),
+    ShowHelp,
+}
+
+impl Mode {
+    /// Which key binding table applies in this mode
+    pub fn keymap_mode(&self) -> KeymapMode {
+        match self {
+            Mode::Normal | Mode::ShowHelp => KeymapMode::Normal,
+            Mode::Search => KeymapMode::Search,
+            _ => KeymapMode::Prompt,
+        }
+    }
+}
+
+/// Work requested by `App::update` that reaches outside the TUI state
+#[derive(Debug, Clone, PartialEq)]
+pub enum Effect {
+    /// Leave without printing anything
+    Exit,
+    /// Print `path` for the shell wrapper and exit
+    Select(String),
+    SetBookmark {
+        path: String,
+        name: String,
+        key: String,
+    },
+    /// Reassign `key` after the user confirmed the conflict
+    OverwriteBookmark {
+        path: String,
+        name: String,
+        key: String,
+    },
+    RemoveBookmark {
+        key: String,
+    },
+    /// Drop a missing directory's bookmark and history
+    ForgetEntry {
+        path: String,
+    },
+    /// Move `key` from the missing `from` to `to`, forgetting `from`
+    RepointBookmark {
+        key: String,
+        from: String,
+        to: String,
+    },
+    CreateEntry {
+        path: PathBuf,
+        is_dir: bool,
+    },
+}
+
+pub type Effects = Vec<Effect>;
+
+pub struct App {
+    pub state: FuzzyState,
+    pub mode: Mode,
+}
+
+impl App {
+    pub fn new(state: FuzzyState) -> Self {
+        Self {
+            state,
+            mode: Mode::Normal,
+        }
+    }
+
+    /// Apply one resolved key press
+    pub fn update(&mut self, event: InputEvent) -> Effects {
+        if !matches!(event, InputEvent::None) {
+            self.state.status = None;
+        }
+        match event {
+            InputEvent::None => Vec::new(),
+            // Any key leaves the help screen
+            _ if self.mode == Mode::ShowHelp => {
+                self.mode = Mode::Normal;
+                Vec::new()
+            }
+            InputEvent::Char(c) => {
+                self.type_char(c);
+                Vec::new()
+            }
+            InputEvent::Action(action) => self.apply(action),
+        }
+    }
+
+    /// `Effect::SetBookmark` found `alias` in use: ask before overwriting it
+    pub fn confirm_overwrite(&mut self, alias: &str, existing_path: String) {
+        self.mode = Mode::BookmarkOverwrite {
+            alias: alias.to_string(),
+            existing_path,
+        };
+    }
+
+    fn type_char(&mut self, c: char) {
+        match self.mode {
+            Mode::Search => self.state.add_char(c),
+            Mode::BookmarkInput(ref mut input)
+            | Mode::BookmarkRepoint(ref mut input)
+            | Mode::CreateEntry(ref mut input) => input.insert(c),
+            Mode::Normal => {
+                if let Some(digit) = c.to_digit(10) {
+                    let count = self.state.motion_count.unwrap_or(0) * 10 + digit as usize;
+                    self.state.set_motion_count(count);
+                }
+            }
+            Mode::BookmarkOverwrite { .. }
+            | Mode::BookmarkRemove
+            | Mode::ForgetEntry
+            | Mode::ShowHelp => {}
+        }
+    }
+
+    fn apply(&mut self, action: Action) -> Effects {
+        match action {
+            Action::Cancel => match self.mode {
+                Mode::Normal => return vec![Effect::Exit],
+                Mode::Search => {
+                    self.state.clear_query();
+                    self.mode = Mode::Normal;
+                }
+                _ => self.mode = Mode::Normal,
+            },
+            Action::Select => return self.select(),
+            Action::DeleteChar => match self.mode {
+                Mode::Search => {
+                    self.state.pop_char();
+                    if self.state.search_query.is_empty() {
+                        self.mode = Mode::Normal;
+                    }
+                }
+                Mode::BookmarkInput(ref mut input)
+                | Mode::BookmarkRepoint(ref mut input)
+                | Mode::CreateEntry(ref mut input) => input.backspace(),
+                _ => {}
+            },
+            Action::CursorLeft
+            | Action::CursorRight
+            | Action::CursorStart
+            | Action::CursorEnd
+            | Action::DeleteForward
+            | Action::DeleteWord
+            | Action::DeleteToStart => self.edit_input(action),
+            Action::MoveUp | Action::MoveDown => {
+                let count = match self.mode {
+                    Mode::Normal => self.state.take_motion_count(),
+                    Mode::Search => 1,
+                    _ => 0,
+                };
+                for _ in 0..count {
+                    if action == Action::MoveUp {
+                        self.state.move_up();
+                    } else {
+                        self.state.move_down();
+                    }
+                }
+            }
+            Action::PageUp | Action::PageDown | Action::GoToStart | Action::GoToEnd => {
+                if matches!(self.mode, Mode::Normal | Mode::Search) {
+                    match action {
+                        Action::PageUp => self.state.page_up(),
+                        Action::PageDown => self.state.page_down(),
+                        Action::GoToStart => self.state.go_to_start(),
+                        _ => self.state.go_to_end(),
+                    }
+                }
+            }
+            // Missing entries are only listed in search results, so these work there too
+            Action::RemoveBookmark | Action::RepointBookmark => {
+                if matches!(self.mode, Mode::Normal | Mode::Search) {
+                    if let Some(mode) = self.entry_prompt(action) {
+                        self.mode = mode;
+                    }
+                }
+            }
+            // The remaining actions only apply while browsing
+            _ if self.mode != Mode::Normal => {}
+            Action::StartSearch => self.mode = Mode::Search,
+            Action::NavigateIn => self.state.navigate_into(),
+            Action::NavigateOut => self.state.navigate_back(),
+            Action::ToggleHidden => self.state.toggle_hidden(),
+            Action::ToggleFiles => self.state.toggle_files(),
+            Action::ToggleIgnored => self.state.toggle_ignored(),
+            Action::ToggleDeep => self.state.toggle_deep(),
+            Action::ToggleColumn(column) => self.state.toggle_column(column),
+            Action::CycleSort => self.state.cycle_sort(),
+            Action::ReverseSort => self.state.reverse_sort(),
+            Action::TogglePreview => self.state.toggle_preview(),
+            Action::Bookmark => {
+                if self.state.selected_item().is_some() {
+                    self.mode = Mode::BookmarkInput(TextInput::new());
+                }
+            }
+            Action::Create => self.mode = Mode::CreateEntry(TextInput::new()),
+            Action::ShowHelp => self.mode = Mode::ShowHelp,
+        }
+        Vec::new()
+    }
+
+    /// The prompt `x` or `R` opens for the selected entry, if it applies to it
+    fn entry_prompt(&self, action: Action) -> Option<Mode> {
+        let item = self.state.selected_item()?;
+        match action {
+            Action::RemoveBookmark if item.is_stale => Some(Mode::ForgetEntry),
+            Action::RemoveBookmark if item.is_bookmark => Some(Mode::BookmarkRemove),
+            Action::RepointBookmark if item.is_stale && item.is_bookmark => {
+                let mut target = TextInput::new();
+                target.set(&item.path());
+                Some(Mode::BookmarkRepoint(target))
+            }
+            _ => None,
+        }
+    }
+
+    /// Enter: confirm the current prompt, or pick the selected entry
+    fn select(&mut self) -> Effects {
+        let mode = std::mem::replace(&mut self.mode, Mode::Normal);
+        let selected = self
+            .state
+            .selected_item()
+            .map(|item| (item.path(), item.entry.name.clone()));
+
+        let effect = match mode {
+            Mode::BookmarkInput(alias) if !alias.is_empty() => {
+                selected.map(|(path, name)| Effect::SetBookmark {
+                    path,
+                    name,
+                    key: alias.to_string(),
+                })
+            }
+            Mode::BookmarkOverwrite { alias, .. } => {
+                selected.map(|(path, name)| Effect::OverwriteBookmark {
+                    path,
+                    name,
+                    key: alias,
+                })
+            }
+            Mode::BookmarkRemove => self
+                .state
+                .selected_item()
+                .and_then(|item| item.bookmark_key.clone())
+                .map(|key| Effect::RemoveBookmark { key }),
+            Mode::ForgetEntry => selected.map(|(path, _)| Effect::ForgetEntry { path }),
+            Mode::BookmarkRepoint(target) if !target.is_empty() => {
+                let to = fs::normalize_path(&target, &self.state.current_dir, false);
+                self.state
+                    .selected_item()
+                    .and_then(|item| item.bookmark_key.clone())
+                    .zip(selected)
+                    .map(|(key, (from, _))| Effect::RepointBookmark {
+                        key,
+                        from,
+                        to: to.to_string_lossy().into_owned(),
+                    })
+            }
+            Mode::CreateEntry(name) if !name.is_empty() => Some(Effect::CreateEntry {
+                path: self.state.current_dir.join(name.trim_end_matches('/')),
+                is_dir: name.ends_with('/'),
+            }),
+            Mode::BookmarkInput(_) | Mode::BookmarkRepoint(_) | Mode::CreateEntry(_) => None,
+            Mode::Normal | Mode::Search | Mode::ShowHelp => {
+                // If no items (e.g., empty directory), select current directory
+                let path = selected
+                    .map(|(path, _)| path)
+                    .unwrap_or_else(|| self.state.current_dir.to_string_lossy().into_owned());
+                Some(Effect::Select(self.state.selection_path(path)))
+            }
+        };
+        effect.into_iter().collect()
+    }
+
+    fn edit_input(&mut self, action: Action) {
+        let edit = |input: &mut TextInput| match action {
+            Action::CursorLeft => input.move_left(),
+            Action::CursorRight => input.move_right(),
+            Action::CursorStart => input.move_home(),
+            Action::CursorEnd => input.move_end(),
+            Action::DeleteForward => input.delete(),
+            Action::DeleteWord => input.delete_word(),
+            _ => input.delete_to_start(),
+        };
+        match self.mode {
+            Mode::Search => self.state.edit_query(edit),
+            Mode::BookmarkInput(ref mut input)
+            | Mode::BookmarkRepoint(ref mut input)
+            | Mode::CreateEntry(ref mut input) => edit(input),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::entry::DirEntry as DbDirEntry;
+    use crate::fs::DirEntry;
+
+    fn app(names: &[&str]) -> App {
+        let entries = names
+            .iter()
+            .map(|name| DirEntry {
+                path: PathBuf::from(format!("/test/{}", name)),
+                name: name.to_string(),
+                is_dir: true,
+                link: None,
+                meta: Default::default(),
+            })
+            .collect();
+        App::new(FuzzyState::with_entries(entries))
+    }
+
+    fn act(app: &mut App, action: Action) -> Effects {
+        app.update(InputEvent::Action(action))
+    }
+
+    fn type_str(app: &mut App, text: &str) {
+        for c in text.chars() {
+            assert!(app.update(InputEvent::Char(c)).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_motion_count_and_select() {
+        let mut app = app(&["a", "b", "c", "d"]);
+        type_str(&mut app, "2");
+        act(&mut app, Action::MoveDown);
+        assert_eq!(app.state.selected_index, 2);
+        act(&mut app, Action::MoveUp);
+        assert_eq!(app.state.selected_index, 1);
+
+        assert_eq!(
+            act(&mut app, Action::Select),
+            vec![Effect::Select("/test/b".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_select_in_empty_directory_picks_current_dir() {
+        let mut app = app(&[]);
+        assert_eq!(
+            act(&mut app, Action::Select),
+            vec![Effect::Select("/".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_search_mode() {
+        let mut app = app(&["apple", "banana"]);
+        act(&mut app, Action::StartSearch);
+        assert_eq!(app.mode, Mode::Search);
+
+        // Letters bound in normal mode are typed into the query
+        type_str(&mut app, "ban");
+        assert_eq!(app.state.result_count(), 1);
+
+        act(&mut app, Action::DeleteChar);
+        act(&mut app, Action::DeleteChar);
+        act(&mut app, Action::DeleteChar);
+        assert_eq!(app.mode, Mode::Normal);
+
+        act(&mut app, Action::StartSearch);
+        type_str(&mut app, "app");
+        act(&mut app, Action::Cancel);
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.state.search_query.is_empty());
+        assert_eq!(act(&mut app, Action::Cancel), vec![Effect::Exit]);
+    }
+
+    #[test]
+    fn test_bookmark_input_and_overwrite() {
+        let mut app = app(&["work"]);
+        act(&mut app, Action::Bookmark);
+        type_str(&mut app, "wx");
+        act(&mut app, Action::DeleteChar);
+        assert_eq!(
+            act(&mut app, Action::Select),
+            vec![Effect::SetBookmark {
+                path: "/test/work".to_string(),
+                name: "work".to_string(),
+                key: "w".to_string(),
+            }]
+        );
+        assert_eq!(app.mode, Mode::Normal);
+
+        app.confirm_overwrite("w", "/elsewhere".to_string());
+        assert_eq!(
+            act(&mut app, Action::Select),
+            vec![Effect::OverwriteBookmark {
+                path: "/test/work".to_string(),
+                name: "work".to_string(),
+                key: "w".to_string(),
+            }]
+        );
+
+        // An empty alias does nothing
+        act(&mut app, Action::Bookmark);
+        assert!(act(&mut app, Action::Select).is_empty());
+        assert_eq!(app.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn test_remove_bookmark_requires_bookmark() {
+        let mut app = app(&["plain"]);
+        act(&mut app, Action::RemoveBookmark);
+        assert_eq!(app.mode, Mode::Normal);
+
+        app.state.set_bookmarks(vec![DbDirEntry {
+            path: "/test/plain".to_string(),
+            name: "plain".to_string(),
+            is_bookmark: true,
+            bookmark_key: Some("p".to_string()),
+            access_count: 0,
+            last_accessed: None,
+        }]);
+        act(&mut app, Action::RemoveBookmark);
+        assert_eq!(app.mode, Mode::BookmarkRemove);
+        // Movement is ignored while confirming
+        act(&mut app, Action::MoveDown);
+        assert_eq!(
+            act(&mut app, Action::Select),
+            vec![Effect::RemoveBookmark {
+                key: "p".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_forget_and_repoint_missing_entries() {
+        let mut app = app(&["work"]);
+        let entry = |path: &str, key: Option<&str>| DbDirEntry {
+            path: path.to_string(),
+            name: path.rsplit('/').next().unwrap().to_string(),
+            is_bookmark: key.is_some(),
+            bookmark_key: key.map(String::from),
+            access_count: 1,
+            last_accessed: Some(1),
+        };
+        app.state.set_bookmarks(vec![
+            entry("/gone/work", Some("w")),
+            entry("/test/work", Some("t")),
+        ]);
+        app.state.set_frecent(vec![entry("/gone/old", None)]);
+        app.state
+            .set_stale_paths(vec!["/gone/work".to_string(), "/gone/old".to_string()]);
+
+        act(&mut app, Action::StartSearch);
+        type_str(&mut app, "old");
+        act(&mut app, Action::RemoveBookmark);
+        assert_eq!(app.mode, Mode::ForgetEntry);
+        assert_eq!(
+            act(&mut app, Action::Select),
+            vec![Effect::ForgetEntry {
+                path: "/gone/old".to_string()
+            }]
+        );
+
+        // Entries that exist have nothing to repoint
+        app.state.set_query("work");
+        act(&mut app, Action::MoveDown);
+        assert_eq!(app.state.selected_item().unwrap().path(), "/test/work");
+        act(&mut app, Action::RepointBookmark);
+        assert_eq!(app.mode, Mode::Normal);
+
+        act(&mut app, Action::MoveUp);
+        act(&mut app, Action::RepointBookmark);
+        assert!(matches!(app.mode, Mode::BookmarkRepoint(ref t) if **t == *"/gone/work"));
+        act(&mut app, Action::DeleteToStart);
+        type_str(&mut app, "/srv/x/../work/");
+        assert_eq!(
+            act(&mut app, Action::Select),
+            vec![Effect::RepointBookmark {
+                key: "w".to_string(),
+                from: "/gone/work".to_string(),
+                to: "/srv/work".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_create_entry() {
+        let mut app = app(&[]);
+        act(&mut app, Action::Create);
+        type_str(&mut app, "notes/");
+        assert_eq!(
+            act(&mut app, Action::Select),
+            vec![Effect::CreateEntry {
+                path: PathBuf::from("/notes"),
+                is_dir: true,
+            }]
+        );
+
+        act(&mut app, Action::Create);
+        type_str(&mut app, "todo.md");
+        act(&mut app, Action::CursorStart);
+        act(&mut app, Action::DeleteForward);
+        assert_eq!(
+            act(&mut app, Action::Select),
+            vec![Effect::CreateEntry {
+                path: PathBuf::from("/odo.md"),
+                is_dir: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_help_closes_on_any_key() {
+        let mut app = app(&["a", "b"]);
+        act(&mut app, Action::ShowHelp);
+        assert_eq!(app.mode, Mode::ShowHelp);
+        assert!(act(&mut app, Action::MoveDown).is_empty());
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(app.state.selected_index, 0);
+    }
+}
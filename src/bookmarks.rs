@@ -0,0 +1,174 @@
+//! Bookmark export and import for syncing through dotfiles
+//!
+//! Paths under `$HOME` are written as `~/...` so the same file works on
+//! machines with different usernames, and expanded again on import.
+
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::database::entry::DirEntry as DbDirEntry;
+
+/// Serialization format for exported bookmarks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BookmarkFormat {
+    #[default]
+    Json,
+    Toml,
+}
+
+impl FromStr for BookmarkFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "json" => Ok(BookmarkFormat::Json),
+            "toml" => Ok(BookmarkFormat::Toml),
+            other => anyhow::bail!(
+                "Unknown bookmark format '{}' (expected json or toml)",
+                other
+            ),
+        }
+    }
+}
+
+impl BookmarkFormat {
+    /// Guess the format from a file extension, defaulting to JSON
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => BookmarkFormat::Toml,
+            _ => BookmarkFormat::Json,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookmarkRecord {
+    pub key: String,
+    pub name: String,
+    pub path: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BookmarkFile {
+    #[serde(default)]
+    bookmarks: Vec<BookmarkRecord>,
+}
+
+/// Replace a leading `home` with `~`
+pub fn collapse_home(path: &str, home: &Path) -> String {
+    match Path::new(path).strip_prefix(home) {
+        Ok(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Ok(rest) => format!("~/{}", rest.display()),
+        Err(_) => path.to_string(),
+    }
+}
+
+/// Replace a leading `~` with `home`
+pub fn expand_home(path: &str, home: &Path) -> String {
+    if path == "~" {
+        home.to_string_lossy().into_owned()
+    } else if let Some(rest) = path.strip_prefix("~/") {
+        home.join(rest).to_string_lossy().into_owned()
+    } else {
+        path.to_string()
+    }
+}
+
+/// Serialize bookmarks with `$HOME`-relative paths
+pub fn export(bookmarks: &[DbDirEntry], format: BookmarkFormat, home: &Path) -> Result<String> {
+    let file = BookmarkFile {
+        bookmarks: bookmarks
+            .iter()
+            .filter_map(|b| {
+                Some(BookmarkRecord {
+                    key: b.bookmark_key.clone()?,
+                    name: b.name.clone(),
+                    path: collapse_home(&b.path, home),
+                })
+            })
+            .collect(),
+    };
+
+    match format {
+        BookmarkFormat::Json => Ok(serde_json::to_string_pretty(&file)?),
+        BookmarkFormat::Toml => Ok(toml::to_string(&file)?),
+    }
+}
+
+/// Parse an exported bookmark file, expanding `~` to `home`
+pub fn parse(content: &str, format: BookmarkFormat, home: &Path) -> Result<Vec<BookmarkRecord>> {
+    let file: BookmarkFile = match format {
+        BookmarkFormat::Json => {
+            serde_json::from_str(content).context("Invalid JSON bookmark file")?
+        }
+        BookmarkFormat::Toml => toml::from_str(content).context("Invalid TOML bookmark file")?,
+    };
+
+    Ok(file
+        .bookmarks
+        .into_iter()
+        .map(|mut b| {
+            b.path = expand_home(&b.path, home);
+            b
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(key: &str, path: &str) -> DbDirEntry {
+        DbDirEntry {
+            path: path.to_string(),
+            name: key.to_string(),
+            is_bookmark: true,
+            bookmark_key: Some(key.to_string()),
+            access_count: 0,
+            last_accessed: None,
+        }
+    }
+
+    #[test]
+    fn test_collapse_and_expand_home() {
+        let home = Path::new("/home/alice");
+        assert_eq!(collapse_home("/home/alice/src", home), "~/src");
+        assert_eq!(collapse_home("/home/alice", home), "~");
+        assert_eq!(collapse_home("/home/alicebob", home), "/home/alicebob");
+        assert_eq!(
+            expand_home("~/src", Path::new("/home/bob")),
+            "/home/bob/src"
+        );
+        assert_eq!(expand_home("/opt", Path::new("/home/bob")), "/opt");
+    }
+
+    #[test]
+    fn test_roundtrip_between_homes() {
+        let bookmarks = vec![bookmark("w", "/home/alice/work"), bookmark("o", "/opt")];
+        for format in [BookmarkFormat::Json, BookmarkFormat::Toml] {
+            let text = export(&bookmarks, format, Path::new("/home/alice")).unwrap();
+            assert!(text.contains("~/work"));
+
+            let parsed = parse(&text, format, Path::new("/home/bob")).unwrap();
+            assert_eq!(parsed.len(), 2);
+            assert_eq!(parsed[0].key, "w");
+            assert_eq!(parsed[0].path, "/home/bob/work");
+            assert_eq!(parsed[1].path, "/opt");
+        }
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            BookmarkFormat::from_path(Path::new("b.toml")),
+            BookmarkFormat::Toml
+        );
+        assert_eq!(
+            BookmarkFormat::from_path(Path::new("b.json")),
+            BookmarkFormat::Json
+        );
+    }
+}
@@ -1,28 +1,62 @@
+//! Command-line parsing and help text
+
 use std::env;
-use std::io::Write;
+use std::iter::Peekable;
+use std::slice::Iter;
+
+use crate::bookmarks::BookmarkFormat;
+use crate::import::ImportSource;
+use crate::shell::Shell;
 
-use crate::shell::{print_completion, print_shell_init, Shell};
+pub mod settings;
 
-const VERSION: &str = env!("CARGO_PKG_VERSION");
-const NAME: &str = env!("CARGO_PKG_NAME");
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const NAME: &str = env!("CARGO_PKG_NAME");
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Config {
     pub show_hidden: bool,
     pub query: Option<String>,
+    /// Print the best match for `query` instead of opening the TUI
+    pub no_tui: bool,
+    /// Skip the background check for missing bookmark targets at TUI start
+    pub no_stale_check: bool,
+    /// Keys to replay on a headless screen instead of reading the terminal
+    pub script: Option<String>,
+    /// Start in deep search, walking this many levels
+    pub depth: Option<usize>,
 }
 
-pub enum ParseResult {
-    Config(Config),
-    Exit,
+/// A fully parsed command line
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Open the TUI, or print the best match when `no_tui` is set
+    Run(Config),
+    Bookmark(BookmarkAction),
+    Init(Shell),
+    Completions(Shell),
+    Import {
+        source: ImportSource,
+        file: Option<String>,
+    },
+    Prune {
+        dry_run: bool,
+        /// Also remove bookmarks whose directory is gone
+        bookmarks: bool,
+    },
+    Config(ConfigAction),
+    Help(HelpTopic),
+    Version,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum BookmarkAction {
     Add {
         key: String,
         name: Option<String>,
         path: Option<String>,
+        force: bool,
+        resolve_symlinks: bool,
     },
     Remove {
         key: String,
@@ -31,151 +65,528 @@ pub enum BookmarkAction {
     Jump {
         key: String,
     },
-    None,
+    Export {
+        format: BookmarkFormat,
+    },
+    Import {
+        file: String,
+        format: Option<BookmarkFormat>,
+        replace: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigAction {
+    /// Print the effective settings after the file and environment are applied
+    Show,
+    PrintDefaults,
+    Path,
+}
+
+/// Which help text (and usage line) a command or error refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpTopic {
+    Main,
+    Bookmark,
+    Init,
+    Completions,
+    Import,
+    Prune,
+    Config,
+}
+
+impl HelpTopic {
+    fn usage(self) -> &'static str {
+        match self {
+            HelpTopic::Main => "[OPTIONS] [QUERY]",
+            HelpTopic::Bookmark => "bookmark <add|remove|list|jump|export|import> [ARGS]",
+            HelpTopic::Init => "init [bash|zsh|fish|auto]",
+            HelpTopic::Completions => "completions [bash|zsh|fish]",
+            HelpTopic::Import => "import --from <zoxide|autojump|z|fasd> [FILE]",
+            HelpTopic::Prune => "prune [--dry-run] [--bookmarks]",
+            HelpTopic::Config => "config [--print-defaults|--path]",
+        }
+    }
+
+    fn help_command(self) -> &'static str {
+        match self {
+            HelpTopic::Main => "--help",
+            HelpTopic::Bookmark => "bookmark --help",
+            HelpTopic::Init => "init --help",
+            HelpTopic::Completions => "completions --help",
+            HelpTopic::Import => "import --help",
+            HelpTopic::Prune => "prune --help",
+            HelpTopic::Config => "config --help",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum CliError {
+    #[error("unknown option '{arg}'")]
+    UnknownOption { arg: String, topic: HelpTopic },
+    #[error("unknown subcommand '{name}'")]
+    UnknownSubcommand { name: String, topic: HelpTopic },
+    #[error("missing {what}")]
+    MissingArgument { what: String, topic: HelpTopic },
+    #[error("unexpected argument '{arg}'")]
+    UnexpectedArgument { arg: String, topic: HelpTopic },
+    #[error("invalid value '{value}' for {option}: {reason}")]
+    InvalidValue {
+        option: String,
+        value: String,
+        reason: String,
+        topic: HelpTopic,
+    },
+}
+
+impl CliError {
+    pub fn topic(&self) -> HelpTopic {
+        match self {
+            CliError::UnknownOption { topic, .. }
+            | CliError::UnknownSubcommand { topic, .. }
+            | CliError::MissingArgument { topic, .. }
+            | CliError::UnexpectedArgument { topic, .. }
+            | CliError::InvalidValue { topic, .. } => *topic,
+        }
+    }
 }
 
-pub fn parse_args() -> (ParseResult, BookmarkAction) {
+type Args<'a> = Peekable<Iter<'a, String>>;
+
+pub fn parse_args() -> Result<Command, CliError> {
     let args: Vec<String> = env::args().skip(1).collect();
-    let mut bookmark_action = BookmarkAction::None;
+    parse(&args)
+}
 
-    let mut show_hidden = false;
-    let mut query: Option<String> = None;
+/// Parse command-line arguments (without the program name)
+pub fn parse(args: &[String]) -> Result<Command, CliError> {
+    let mut config = Config::default();
 
     let mut iter = args.iter().peekable();
     while let Some(arg) = iter.next() {
         match arg.as_str() {
-            "-h" | "--help" => {
-                print_help();
-                std::io::stdout().flush().ok();
-                return (ParseResult::Exit, BookmarkAction::None);
-            }
-            "-v" | "--version" => {
-                println!("{} {}", NAME, VERSION);
-                std::io::stdout().flush().ok();
-                std::process::exit(0);
-            }
-            "-a" | "--all" => {
-                show_hidden = true;
-            }
-            "-b" | "--bookmark" => {
-                // Handle bookmark subcommand
-                let subcommand = iter.next().map(|s| s.as_str()).unwrap_or("");
-                bookmark_action = match subcommand {
-                    "add" | "a" => {
-                        let key = iter.next().expect("Bookmark key required").clone();
-                        let name_or_path = iter.next().cloned();
-                        // Try to determine if it's a name or path
-                        let name = name_or_path.clone();
-                        let path =
-                            name_or_path.filter(|s| s.starts_with('/') || s.starts_with("~"));
-                        BookmarkAction::Add { key, name, path }
-                    }
-                    "remove" | "rm" | "del" => {
-                        let key = iter.next().expect("Bookmark key required").clone();
-                        BookmarkAction::Remove { key }
-                    }
-                    "list" | "ls" => BookmarkAction::List,
-                    "jump" | "j" => {
-                        let key = iter.next().expect("Bookmark key required").clone();
-                        BookmarkAction::Jump { key }
-                    }
-                    "" | "help" | "h" => {
-                        print_bookmark_help();
-                        std::io::stdout().flush().ok();
-                        return (ParseResult::Exit, BookmarkAction::None);
-                    }
+            "-h" | "--help" => return Ok(Command::Help(HelpTopic::Main)),
+            "-v" | "--version" => return Ok(Command::Version),
+            "-a" | "--all" => config.show_hidden = true,
+            "--no-tui" => config.no_tui = true,
+            "--no-stale-check" => config.no_stale_check = true,
+            "--depth" => {
+                let value = option_value(&mut iter, "--depth", HelpTopic::Main)?;
+                config.depth = match value.parse() {
+                    Ok(depth) if depth > 0 => Some(depth),
                     _ => {
-                        eprintln!("Unknown bookmark subcommand: {}", subcommand);
-                        print_bookmark_help();
-                        std::process::exit(1);
+                        return Err(CliError::InvalidValue {
+                            option: "--depth".to_string(),
+                            value: value.to_string(),
+                            reason: "expected a positive integer".to_string(),
+                            topic: HelpTopic::Main,
+                        })
                     }
                 };
             }
-            "--shell-init" => {
-                let shell = args
-                    .iter()
-                    .find(|a| *a != "--shell-init")
-                    .and_then(|a| match a.as_str() {
-                        "bash" => Some(Shell::Bash),
-                        "zsh" => Some(Shell::Zsh),
-                        "fish" => Some(Shell::Fish),
-                        "auto" => Shell::detect(),
-                        _ => None,
-                    })
-                    .unwrap_or_else(|| Shell::detect().unwrap_or(Shell::Bash));
-
-                let _ = print_shell_init(shell);
-                return (ParseResult::Exit, BookmarkAction::None);
+            "--script" => {
+                let keys = option_value(&mut iter, "--script", HelpTopic::Main)?;
+                config.script = Some(keys.to_string());
+            }
+            // Flag forms kept as aliases of the subcommands
+            "-b" | "--bookmark" => return parse_bookmark(&mut iter),
+            "--shell-init" => return parse_init(&mut iter),
+            "--completions" => return parse_completions(&mut iter),
+            "--" => {
+                for rest in iter.by_ref() {
+                    set_query(&mut config, rest)?;
+                }
+            }
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(unknown_option(flag, HelpTopic::Main));
+            }
+            // The first positional may be a subcommand; otherwise it is the query
+            word if config.query.is_none() && !config.no_tui => match word {
+                "query" => config.no_tui = true,
+                "bookmark" => return parse_bookmark(&mut iter),
+                "init" => return parse_init(&mut iter),
+                "completions" => return parse_completions(&mut iter),
+                "import" => return parse_import(&mut iter),
+                "prune" => return parse_prune(&mut iter),
+                "config" => return parse_config(&mut iter),
+                _ => config.query = Some(word.to_string()),
+            },
+            word => set_query(&mut config, word)?,
+        }
+    }
+
+    if config.no_tui && config.query.is_none() {
+        return Err(CliError::MissingArgument {
+            what: "search term for query mode".to_string(),
+            topic: HelpTopic::Main,
+        });
+    }
+
+    Ok(Command::Run(config))
+}
+
+fn set_query(config: &mut Config, word: &str) -> Result<(), CliError> {
+    if config.query.is_some() {
+        return Err(unexpected(word, HelpTopic::Main));
+    }
+    config.query = Some(word.to_string());
+    Ok(())
+}
+
+fn unknown_option(arg: &str, topic: HelpTopic) -> CliError {
+    CliError::UnknownOption {
+        arg: arg.to_string(),
+        topic,
+    }
+}
+
+fn unexpected(arg: &str, topic: HelpTopic) -> CliError {
+    CliError::UnexpectedArgument {
+        arg: arg.to_string(),
+        topic,
+    }
+}
+
+fn missing(what: &str, topic: HelpTopic) -> CliError {
+    CliError::MissingArgument {
+        what: what.to_string(),
+        topic,
+    }
+}
+
+fn is_help(arg: &str) -> bool {
+    matches!(arg, "-h" | "--help" | "help")
+}
+
+/// Take the value following `option`
+fn option_value<'a>(
+    iter: &mut Args<'a>,
+    option: &str,
+    topic: HelpTopic,
+) -> Result<&'a str, CliError> {
+    iter.next()
+        .map(|s| s.as_str())
+        .ok_or_else(|| missing(&format!("value for {}", option), topic))
+}
+
+/// Take a required positional argument
+fn required<'a>(iter: &mut Args<'a>, what: &str, topic: HelpTopic) -> Result<&'a str, CliError> {
+    match iter.next().map(|s| s.as_str()) {
+        Some(arg) if arg.starts_with('-') && arg.len() > 1 => Err(unknown_option(arg, topic)),
+        Some(arg) => Ok(arg),
+        None => Err(missing(what, topic)),
+    }
+}
+
+/// Reject anything left over after a subcommand's arguments
+fn finish(iter: &mut Args, topic: HelpTopic) -> Result<(), CliError> {
+    match iter.next() {
+        Some(arg) => Err(unexpected(arg, topic)),
+        None => Ok(()),
+    }
+}
+
+fn parse_bookmark(iter: &mut Args) -> Result<Command, CliError> {
+    const TOPIC: HelpTopic = HelpTopic::Bookmark;
+
+    let subcommand = match iter.next() {
+        None => return Ok(Command::Help(TOPIC)),
+        Some(s) if is_help(s) || s == "h" => return Ok(Command::Help(TOPIC)),
+        Some(s) => s.as_str(),
+    };
+    if iter.peek().is_some_and(|a| is_help(a)) {
+        return Ok(Command::Help(TOPIC));
+    }
+
+    let action = match subcommand {
+        "add" | "a" => {
+            let mut force = false;
+            let mut resolve_symlinks = false;
+            let mut positional = Vec::new();
+            for arg in iter.by_ref() {
+                match arg.as_str() {
+                    "-f" | "--force" => force = true,
+                    "-r" | "--resolve" => resolve_symlinks = true,
+                    flag if flag.starts_with('-') && flag.len() > 1 => {
+                        return Err(unknown_option(flag, TOPIC));
+                    }
+                    _ if positional.len() == 2 => return Err(unexpected(arg, TOPIC)),
+                    _ => positional.push(arg.clone()),
+                }
             }
-            "--completions" => {
-                let shell = args
-                    .iter()
-                    .find(|a| *a != "--completions")
-                    .and_then(|a| match a.as_str() {
-                        "bash" => Some(Shell::Bash),
-                        "zsh" => Some(Shell::Zsh),
-                        "fish" => Some(Shell::Fish),
-                        _ => None,
-                    })
-                    .unwrap_or_else(|| Shell::detect().unwrap_or(Shell::Bash));
-
-                let _ = print_completion(shell, NAME);
-                return (ParseResult::Exit, BookmarkAction::None);
+            let mut positional = positional.into_iter();
+            let key = positional
+                .next()
+                .ok_or_else(|| missing("bookmark key", TOPIC))?;
+            // Try to determine if it's a name or path
+            let (name, path) = match positional.next() {
+                Some(arg) if looks_like_path(&arg) => (None, Some(arg)),
+                other => (other, None),
+            };
+            BookmarkAction::Add {
+                key,
+                name,
+                path,
+                force,
+                resolve_symlinks,
             }
-            // Handle query arguments
-            arg if arg.starts_with('-') => {
-                // Unknown flag, ignore for now
+        }
+        "remove" | "rm" | "del" => BookmarkAction::Remove {
+            key: required(iter, "bookmark key", TOPIC)?.to_string(),
+        },
+        "list" | "ls" => BookmarkAction::List,
+        "jump" | "j" => BookmarkAction::Jump {
+            key: required(iter, "bookmark key", TOPIC)?.to_string(),
+        },
+        "export" => {
+            let mut format = BookmarkFormat::default();
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--format" => format = parse_format(option_value(iter, arg, TOPIC)?)?,
+                    flag if flag.starts_with('-') => return Err(unknown_option(flag, TOPIC)),
+                    _ => return Err(unexpected(arg, TOPIC)),
+                }
             }
-            _ => {
-                // Treat as query
-                if query.is_none() {
-                    query = Some(arg.clone());
+            BookmarkAction::Export { format }
+        }
+        "import" => {
+            let mut file: Option<String> = None;
+            let mut format: Option<BookmarkFormat> = None;
+            let mut replace = false;
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--merge" => replace = false,
+                    "--replace" => replace = true,
+                    "--format" => format = Some(parse_format(option_value(iter, arg, TOPIC)?)?),
+                    flag if flag.starts_with('-') && flag.len() > 1 => {
+                        return Err(unknown_option(flag, TOPIC));
+                    }
+                    _ if file.is_none() => file = Some(arg.clone()),
+                    _ => return Err(unexpected(arg, TOPIC)),
                 }
             }
+            BookmarkAction::Import {
+                file: file.ok_or_else(|| missing("bookmark file", TOPIC))?,
+                format,
+                replace,
+            }
+        }
+        other => {
+            return Err(CliError::UnknownSubcommand {
+                name: other.to_string(),
+                topic: TOPIC,
+            })
+        }
+    };
+
+    finish(iter, TOPIC)?;
+    Ok(Command::Bookmark(action))
+}
+
+fn parse_shell(value: &str, option: &str, topic: HelpTopic) -> Result<Shell, CliError> {
+    match value {
+        "bash" => Ok(Shell::Bash),
+        "zsh" => Ok(Shell::Zsh),
+        "fish" => Ok(Shell::Fish),
+        "auto" => Ok(Shell::detect().unwrap_or(Shell::Bash)),
+        _ => Err(CliError::InvalidValue {
+            option: option.to_string(),
+            value: value.to_string(),
+            reason: "expected bash, zsh, fish or auto".to_string(),
+            topic,
+        }),
+    }
+}
+
+/// Shared by `init` and `completions`: an optional shell name, detected when omitted
+fn parse_shell_command(iter: &mut Args, topic: HelpTopic) -> Result<Option<Shell>, CliError> {
+    let shell = match iter.next().map(|s| s.as_str()) {
+        Some(arg) if is_help(arg) => return Ok(None),
+        Some(arg) if arg.starts_with('-') && arg.len() > 1 => {
+            return Err(unknown_option(arg, topic));
+        }
+        Some(arg) => parse_shell(arg, "shell", topic)?,
+        None => Shell::detect().unwrap_or(Shell::Bash),
+    };
+    finish(iter, topic)?;
+    Ok(Some(shell))
+}
+
+fn parse_init(iter: &mut Args) -> Result<Command, CliError> {
+    Ok(match parse_shell_command(iter, HelpTopic::Init)? {
+        Some(shell) => Command::Init(shell),
+        None => Command::Help(HelpTopic::Init),
+    })
+}
+
+fn parse_completions(iter: &mut Args) -> Result<Command, CliError> {
+    Ok(match parse_shell_command(iter, HelpTopic::Completions)? {
+        Some(shell) => Command::Completions(shell),
+        None => Command::Help(HelpTopic::Completions),
+    })
+}
+
+fn parse_import(iter: &mut Args) -> Result<Command, CliError> {
+    const TOPIC: HelpTopic = HelpTopic::Import;
+
+    let mut source: Option<ImportSource> = None;
+    let mut file: Option<String> = None;
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--from" => {
+                let value = option_value(iter, arg, TOPIC)?;
+                source = Some(value.parse().map_err(|_| CliError::InvalidValue {
+                    option: "--from".to_string(),
+                    value: value.to_string(),
+                    reason: "expected zoxide, autojump, z or fasd".to_string(),
+                    topic: TOPIC,
+                })?);
+            }
+            a if is_help(a) => return Ok(Command::Help(TOPIC)),
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(unknown_option(flag, TOPIC));
+            }
+            _ if file.is_none() => file = Some(arg.clone()),
+            _ => return Err(unexpected(arg, TOPIC)),
         }
     }
 
-    (
-        ParseResult::Config(Config { show_hidden, query }),
-        bookmark_action,
-    )
+    Ok(Command::Import {
+        source: source.ok_or_else(|| missing("import source (--from)", TOPIC))?,
+        file,
+    })
 }
 
-fn print_help() {
+fn parse_prune(iter: &mut Args) -> Result<Command, CliError> {
+    let (mut dry_run, mut bookmarks) = (false, false);
+    for arg in iter.by_ref() {
+        match arg.as_str() {
+            "-n" | "--dry-run" => dry_run = true,
+            "--bookmarks" => bookmarks = true,
+            a if is_help(a) => return Ok(Command::Help(HelpTopic::Prune)),
+            flag if flag.starts_with('-') => return Err(unknown_option(flag, HelpTopic::Prune)),
+            _ => return Err(unexpected(arg, HelpTopic::Prune)),
+        }
+    }
+    Ok(Command::Prune { dry_run, bookmarks })
+}
+
+fn parse_config(iter: &mut Args) -> Result<Command, CliError> {
+    let mut action = ConfigAction::Show;
+    for arg in iter.by_ref() {
+        match arg.as_str() {
+            "--print-defaults" => action = ConfigAction::PrintDefaults,
+            "--path" => action = ConfigAction::Path,
+            a if is_help(a) => return Ok(Command::Help(HelpTopic::Config)),
+            flag if flag.starts_with('-') => return Err(unknown_option(flag, HelpTopic::Config)),
+            _ => return Err(unexpected(arg, HelpTopic::Config)),
+        }
+    }
+    Ok(Command::Config(action))
+}
+
+/// Whether a bookmark argument is a path rather than a display name
+fn looks_like_path(arg: &str) -> bool {
+    arg.starts_with(['/', '~', '.', '$']) || arg.contains('/')
+}
+
+fn parse_format(value: &str) -> Result<BookmarkFormat, CliError> {
+    value.parse().map_err(|_| CliError::InvalidValue {
+        option: "--format".to_string(),
+        value: value.to_string(),
+        reason: "expected json or toml".to_string(),
+        topic: HelpTopic::Bookmark,
+    })
+}
+
+/// Report a parse error with the relevant usage line
+pub fn print_error(err: &CliError) {
+    let topic = err.topic();
+    eprintln!(
+        "{}: {}
+
+USAGE:
+    {} {}
+
+For more information, try '{} {}'.",
+        NAME,
+        err,
+        NAME,
+        topic.usage(),
+        NAME,
+        topic.help_command()
+    );
+}
+
+pub fn print_help(topic: HelpTopic) {
+    match topic {
+        HelpTopic::Main => print_main_help(),
+        HelpTopic::Bookmark => print_bookmark_help(),
+        HelpTopic::Import => print_import_help(),
+        HelpTopic::Init => print_init_help(),
+        HelpTopic::Completions => print_completions_help(),
+        HelpTopic::Prune => print_prune_help(),
+        HelpTopic::Config => print_config_help(),
+    }
+}
+
+fn print_main_help() {
     println!(
         "{} {} - Vim-inspired directory navigation
 
 USAGE:
     {} [OPTIONS] [QUERY]
+    {} <COMMAND> [ARGS]
+
+COMMANDS:
+    query <QUERY>       Print the best matching bookmark, remembered or child
+                        directory (ranked by fuzzy score and frecency)
+    bookmark <ACTION>   Bookmark management (alias: -b, --bookmark)
+    init [SHELL]        Print shell initialization script (alias: --shell-init)
+    completions [SHELL] Print shell completion script (alias: --completions)
+    import --from SRC   Import directory history from another jumper
+    prune [--dry-run]   Remove history for directories that no longer exist
+                        (bookmarks too with --bookmarks)
+    config              Show settings from config.toml and JUMP_* variables
 
 OPTIONS:
     -a, --all           Show hidden directories
+    --no-tui            Print the best match for QUERY without opening the TUI
+    --no-stale-check    Don't check bookmark targets for missing directories
+    --depth N           Start in deep search, matching paths up to N levels down
+    --script KEYS       Replay KEYS (e.g. '3j l /src <enter>') on a headless
+                        80x24 screen with an empty in-memory database; print
+                        the last frame to stderr and the selection to stdout
     -h, --help          Print help information
     -v, --version       Print version information
-    -b, --bookmark      Bookmark management
-    --shell-init [SHELL] Print shell initialization script (bash/zsh/fish/auto)
-    --completions SHELL Print shell completion script (bash/zsh/fish)
 
 KEYBINDINGS:
     /               Start search
-    j / k           Move selection down/up
-    h / l           Navigate to parent/child directory
-    Ctrl+U/D        Page up/down
-    g / G           Go to top/bottom
+    j / k, Up/Down  Move selection down/up (also Tab / Shift+Tab)
+    h / l           Navigate to parent/child directory (also Left/Right)
+    Ctrl+U/D        Page up/down (also PageUp/PageDown)
+    g / G           Go to top/bottom (also Home/End)
+    i               Toggle entries matched by .gitignore/.ignore/.jumpignore
+    r               Toggle deep search across subdirectories
+    c p/o/s/n/m     Toggle permissions/owner/size/items/modified column
+    s / S           Cycle sort (name/modified/size/extension/frecency) / reverse
+    p               Toggle preview of the selected entry
     Enter           Confirm selection
     Backspace       Delete character
+    Ctrl+W / Ctrl+U Delete word / to start of input while typing
     Esc / Ctrl+C    Cancel
 
-BOOKMARK COMMANDS:
-    -b, --bookmark add <key> [name|path]  Add bookmark
-    -b, --bookmark remove <key>           Remove bookmark
-    -b, --bookmark list                    List all bookmarks
-    -b, --bookmark jump <key>              Jump to bookmark
+SEARCH SYNTAX:
+    Space-separated terms must all match; a | between terms matches either.
+    src  fuzzy   'src  exact   ^src  prefix   src$  suffix   !src  exclude
+
+Run '{} <COMMAND> --help' for details on a command.
 
 SHELL INTEGRATION:
     # Add to your shell rc file:
-    eval \"$({} --shell-init)\"",
-        NAME, VERSION, NAME, NAME
+    eval \"$({} init)\"",
+        NAME, VERSION, NAME, NAME, NAME, NAME
     );
 }
 
@@ -184,19 +595,286 @@ fn print_bookmark_help() {
         "{} bookmark - Bookmark management
 
 USAGE:
+    {} bookmark <subcommand> [arguments]
     {} --bookmark <subcommand> [arguments]
 
 SUBCOMMANDS:
-    add <key> [name|path]  Add bookmark for current dir or specified path
+    add <key> [name|path] [--force] [--resolve]
+                         Add bookmark for current dir or specified path;
+                         --force reassigns a key that is already in use,
+                         --resolve stores the target with symlinks resolved
     remove <key>         Remove bookmark by key
     list                 List all bookmarks
     jump <key>           Jump directly to bookmark
+    export [--format json|toml]
+                         Print bookmarks with $HOME-relative paths
+    import <file> [--merge|--replace] [--format json|toml]
+                         Load bookmarks from an exported file (default: merge)
+
+EXAMPLES:
+    {} bookmark add w work           # Bookmark current dir as 'w'
+    {} bookmark add p ~/projects     # Bookmark ~/projects as 'p'
+    {} bookmark list                 # Show all bookmarks
+    {} bookmark jump w               # Jump to 'w' bookmark
+    {} bookmark export --format toml > ~/.dotfiles/jump-bookmarks.toml
+    {} bookmark import ~/.dotfiles/jump-bookmarks.toml --replace",
+        NAME, NAME, NAME, NAME, NAME, NAME, NAME, NAME, NAME
+    );
+}
+
+fn print_init_help() {
+    println!(
+        "{} init - Print shell initialization script
+
+USAGE:
+    {} init [bash|zsh|fish|auto]
+
+The shell is detected from $SHELL when omitted.
+
+EXAMPLES:
+    eval \"$({} init bash)\"          # ~/.bashrc
+    {} init fish | source            # ~/.config/fish/config.fish",
+        NAME, NAME, NAME, NAME
+    );
+}
+
+fn print_completions_help() {
+    println!(
+        "{} completions - Print shell completion script
+
+USAGE:
+    {} completions [bash|zsh|fish]
+
+The shell is detected from $SHELL when omitted.
+
+EXAMPLES:
+    source <({} completions bash)",
+        NAME, NAME, NAME
+    );
+}
+
+fn print_prune_help() {
+    println!(
+        "{} prune - Remove entries for directories that no longer exist
+
+USAGE:
+    {} prune [--dry-run] [--bookmarks]
+
+Bookmarks pointing at missing directories are listed but kept unless
+--bookmarks is given; repoint one with 'jump bookmark add <key> <path> --force'
+or R in the TUI.
+
+OPTIONS:
+    -n, --dry-run   List stale bookmarks and history without removing them
+    --bookmarks     Remove stale bookmarks along with the history",
+        NAME, NAME
+    );
+}
+
+fn print_config_help() {
+    println!(
+        "{} config - Show user settings
+
+USAGE:
+    {} config [--print-defaults|--path]
+
+Settings are read from config.toml in the config directory
+(~/.config/jump on Linux, or $JUMP_CONFIG), then overridden by
+JUMP_* environment variables (JUMP_PAGE_SIZE, JUMP_COLORS_ACCENT, ...)
+and finally by command-line flags.
+
+OPTIONS:
+    --print-defaults    Print a commented config file with every default
+    --path              Print the config file location
+
+EXAMPLES:
+    {} config --print-defaults > ~/.config/jump/config.toml",
+        NAME, NAME, NAME
+    );
+}
+
+fn print_import_help() {
+    println!(
+        "{} import - Import directory history from other jumpers
+
+USAGE:
+    {} import --from <SOURCE> [FILE]
+
+SOURCES:
+    zoxide    Output of 'zoxide query --list --score' (FILE required, - for stdin)
+    autojump  autojump.txt (default: ~/.local/share/autojump/autojump.txt)
+    z         z data file (default: ~/.z)
+    fasd      fasd data file (default: ~/.fasd)
 
 EXAMPLES:
-    {} --bookmark add w work           # Bookmark current dir as 'w'
-    {} --bookmark add p ~/projects     # Bookmark ~/projects as 'p'
-    {} --bookmark list                 # Show all bookmarks
-    {} --bookmark jump w               # Jump to 'w' bookmark",
-        NAME, NAME, NAME, NAME, NAME, NAME
+    zoxide query --list --score | {} import --from zoxide -
+    {} import --from autojump
+    {} import --from z ~/.z",
+        NAME, NAME, NAME, NAME, NAME
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn run_config(line: &str) -> Config {
+        match parse(&args(line)).unwrap() {
+            Command::Run(config) => config,
+            other => panic!("expected Run, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_query_and_flags() {
+        let config = run_config("-a proj");
+        assert!(config.show_hidden);
+        assert_eq!(config.query.as_deref(), Some("proj"));
+        assert!(!config.no_tui);
+
+        let config = run_config("query proj --all");
+        assert!(config.no_tui);
+        assert!(config.show_hidden);
+        assert_eq!(config.query.as_deref(), Some("proj"));
+
+        // `--` lets a query look like a subcommand
+        assert_eq!(run_config("-- prune").query.as_deref(), Some("prune"));
+        assert_eq!(run_config("--depth 4 src").depth, Some(4));
+
+        let args: Vec<String> = ["--script", "3j l <enter>", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let Ok(Command::Run(config)) = parse(&args) else {
+            panic!("expected a run command");
+        };
+        assert_eq!(config.script.as_deref(), Some("3j l <enter>"));
+        assert_eq!(config.query.as_deref(), Some("src"));
+    }
+
+    #[test]
+    fn test_parse_bookmark_subcommand_and_alias() {
+        let expected = Command::Bookmark(BookmarkAction::Add {
+            key: "p".to_string(),
+            name: None,
+            path: Some("~/projects".to_string()),
+            force: true,
+            resolve_symlinks: false,
+        });
+        assert_eq!(
+            parse(&args("bookmark add p ~/projects --force")).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse(&args("-b add --force p ~/projects")).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse(&args("--bookmark ls")).unwrap(),
+            Command::Bookmark(BookmarkAction::List)
+        );
+        assert_eq!(
+            parse(&args("bookmark")).unwrap(),
+            Command::Help(HelpTopic::Bookmark)
+        );
+    }
+
+    #[test]
+    fn test_parse_init_and_completions() {
+        assert_eq!(parse(&args("init zsh")).unwrap(), Command::Init(Shell::Zsh));
+        assert_eq!(
+            parse(&args("--shell-init fish")).unwrap(),
+            Command::Init(Shell::Fish)
+        );
+        assert_eq!(
+            parse(&args("completions bash")).unwrap(),
+            Command::Completions(Shell::Bash)
+        );
+        assert!(matches!(
+            parse(&args("init tcsh")),
+            Err(CliError::InvalidValue {
+                topic: HelpTopic::Init,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse(&args("--frobnicate")).unwrap_err(),
+            CliError::UnknownOption {
+                arg: "--frobnicate".to_string(),
+                topic: HelpTopic::Main
+            }
+        );
+        assert!(matches!(
+            parse(&args("bookmark add")),
+            Err(CliError::MissingArgument {
+                topic: HelpTopic::Bookmark,
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse(&args("bookmark rename a b")),
+            Err(CliError::UnknownSubcommand { .. })
+        ));
+        assert!(matches!(
+            parse(&args("bookmark remove a b")),
+            Err(CliError::UnexpectedArgument { .. })
+        ));
+        assert!(matches!(
+            parse(&args("query")),
+            Err(CliError::MissingArgument { .. })
+        ));
+        assert!(matches!(
+            parse(&args("one two")),
+            Err(CliError::UnexpectedArgument { .. })
+        ));
+        assert!(matches!(
+            parse(&args("import --from fzf")),
+            Err(CliError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse(&args("--depth 0")),
+            Err(CliError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_import_prune_and_config() {
+        assert_eq!(
+            parse(&args("import --from z /tmp/z")).unwrap(),
+            Command::Import {
+                source: ImportSource::Z,
+                file: Some("/tmp/z".to_string())
+            }
+        );
+        assert_eq!(
+            parse(&args("prune --dry-run")).unwrap(),
+            Command::Prune {
+                dry_run: true,
+                bookmarks: false
+            }
+        );
+        assert_eq!(
+            parse(&args("prune --bookmarks")).unwrap(),
+            Command::Prune {
+                dry_run: false,
+                bookmarks: true
+            }
+        );
+        assert_eq!(
+            parse(&args("config --print-defaults")).unwrap(),
+            Command::Config(ConfigAction::PrintDefaults)
+        );
+        assert_eq!(
+            parse(&args("config")).unwrap(),
+            Command::Config(ConfigAction::Show)
+        );
+    }
+}
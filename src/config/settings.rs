@@ -0,0 +1,603 @@
+//! User settings from `config.toml`, overridden by `JUMP_*` environment variables
+
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+use super::Config;
+use crate::keymap::{Keymap, KeymapMode};
+use crate::ui::{Column, SortMode, Theme};
+
+const CONFIG_FILE: &str = "config.toml";
+
+/// Commented defaults printed by `jump config --print-defaults`
+pub const DEFAULT_CONFIG: &str = r##"# jump configuration
+# Location: ~/.config/jump/config.toml (override with JUMP_CONFIG)
+# Settings other than [keys.*] can also be set through the environment, e.g. JUMP_PAGE_SIZE=20
+# or JUMP_COLORS_ACCENT=magenta. Command-line flags take precedence over both.
+
+# Program the `jump init` shell function opens selected files with
+editor = "vim"
+
+# Show hidden entries and files when the TUI starts (toggle with `.` and `f`)
+show_hidden = false
+show_files = false
+
+# List directories before files, whatever the sort
+dirs_first = true
+
+# Order of the listing: name (natural, so v2 sorts before v10), modified, size,
+# extension or frecency. Cycle with `s`, reverse with `S`. Unless sort or
+# sort_reverse is set here or in the environment, the order you leave the picker
+# with is remembered and used on the next start
+# sort = "name"
+# sort_reverse = false
+
+# Rows moved by Ctrl+U / Ctrl+D
+page_size = 10
+
+# Check bookmark targets for missing directories in the background
+check_stale = true
+
+# Print the selection with symlinks resolved (/srv/app-1.4) instead of the path
+# as navigated (/opt/app)
+resolve_symlinks = false
+
+# Deep search walks this many levels below the current directory and matches the
+# query against relative paths (toggle with `r`, or start with --depth N)
+deep_search = false
+search_depth = 8
+
+# Entries matched by .gitignore, .ignore, global git excludes and .jumpignore files
+# are left out of every listing (toggle with `i`)
+show_ignored = false
+
+# Names left out of every listing, ignore files or not (JUMP_EXCLUDE takes a
+# comma-separated list)
+exclude = [".git"]
+
+# Metadata shown before each name: permissions, owner, size, items (entries in a
+# directory) and modified. Toggle them with `c` then p, o, s, n or m
+columns = []
+
+# Preview the selected entry (children of a directory, the first lines of a text
+# file) in a pane this many percent of the list wide (toggle with `p`)
+preview = false
+preview_width = 50
+
+[colors]
+# Color names (blue, lightred, darkgray, ...), "#rrggbb" or a 0-255 index
+accent = "cyan"
+selection = "blue"
+bookmark = "yellow"
+file = "yellow"
+
+# Key bindings for browsing (normal), typing a query (search) and the
+# bookmark/new entry prompts (prompt). Keys look like "j", "G", "ctrl-d",
+# "alt-enter" or "pagedown"; separate the keys of a chord with spaces ("g g").
+# Bind a key to "none" to remove a default. Actions: cancel, select,
+# delete_char, move_up, move_down, page_up, page_down, go_to_start, go_to_end,
+# start_search, navigate_in, navigate_out, toggle_hidden, toggle_files,
+# toggle_ignored, toggle_deep, toggle_permissions, toggle_owner, toggle_size,
+# toggle_items, toggle_modified, cycle_sort, reverse_sort, toggle_preview,
+# bookmark, remove_bookmark, repoint_bookmark, create, help, and for text inputs
+# cursor_left, cursor_right, cursor_start, cursor_end, delete_forward, delete_word,
+# delete_to_start
+[keys.normal]
+# d = "remove_bookmark"
+# left = "navigate_out"
+# right = "navigate_in"
+
+[keys.search]
+# ctrl-n = "move_down"
+# ctrl-p = "move_up"
+
+[keys.prompt]
+"##;
+
+/// Every configurable key with its environment variable
+const KEYS: &[(&str, &str)] = &[
+    ("editor", "JUMP_EDITOR"),
+    ("show_hidden", "JUMP_SHOW_HIDDEN"),
+    ("show_files", "JUMP_SHOW_FILES"),
+    ("dirs_first", "JUMP_DIRS_FIRST"),
+    ("sort", "JUMP_SORT"),
+    ("sort_reverse", "JUMP_SORT_REVERSE"),
+    ("page_size", "JUMP_PAGE_SIZE"),
+    ("check_stale", "JUMP_CHECK_STALE"),
+    ("resolve_symlinks", "JUMP_RESOLVE_SYMLINKS"),
+    ("deep_search", "JUMP_DEEP_SEARCH"),
+    ("search_depth", "JUMP_SEARCH_DEPTH"),
+    ("show_ignored", "JUMP_SHOW_IGNORED"),
+    ("exclude", "JUMP_EXCLUDE"),
+    ("columns", "JUMP_COLUMNS"),
+    ("preview", "JUMP_PREVIEW"),
+    ("preview_width", "JUMP_PREVIEW_WIDTH"),
+    ("colors.accent", "JUMP_COLORS_ACCENT"),
+    ("colors.selection", "JUMP_COLORS_SELECTION"),
+    ("colors.bookmark", "JUMP_COLORS_BOOKMARK"),
+    ("colors.file", "JUMP_COLORS_FILE"),
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum SettingsError {
+    #[error("failed to read {}: {source}", path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("{}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("{origin}: invalid value '{value}' for `{key}`: {reason}")]
+    InvalidValue {
+        origin: String,
+        key: String,
+        value: String,
+        reason: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub editor: String,
+    pub show_hidden: bool,
+    pub show_files: bool,
+    pub dirs_first: bool,
+    /// `SortMode` name
+    pub sort: String,
+    pub sort_reverse: bool,
+    pub page_size: usize,
+    pub check_stale: bool,
+    /// Canonicalize the selected path instead of keeping symlinked components
+    pub resolve_symlinks: bool,
+    pub deep_search: bool,
+    /// Levels below the current directory walked by deep search
+    pub search_depth: usize,
+    /// List entries matched by ignore files
+    pub show_ignored: bool,
+    /// Names skipped by every scan
+    pub exclude: Vec<String>,
+    /// Metadata columns shown at start, by `Column` name
+    pub columns: Vec<String>,
+    pub preview: bool,
+    /// Width of the preview pane in percent of the list area
+    pub preview_width: u16,
+    pub colors: ColorSettings,
+    pub keys: KeySettings,
+    /// `sort` or `sort_reverse` was set by the file or the environment, so the sort
+    /// remembered from the last run doesn't apply
+    #[serde(skip)]
+    pub sort_configured: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColorSettings {
+    pub accent: String,
+    pub selection: String,
+    pub bookmark: String,
+    pub file: String,
+}
+
+/// Per-mode overrides of the default key bindings (key sequence -> action name)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeySettings {
+    pub normal: BTreeMap<String, String>,
+    pub search: BTreeMap<String, String>,
+    pub prompt: BTreeMap<String, String>,
+}
+
+impl KeySettings {
+    fn mode(&self, mode: KeymapMode) -> &BTreeMap<String, String> {
+        match mode {
+            KeymapMode::Normal => &self.normal,
+            KeymapMode::Search => &self.search,
+            KeymapMode::Prompt => &self.prompt,
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            editor: "vim".to_string(),
+            show_hidden: false,
+            show_files: false,
+            dirs_first: true,
+            sort: SortMode::default().name().to_string(),
+            sort_reverse: false,
+            page_size: 10,
+            check_stale: true,
+            resolve_symlinks: false,
+            deep_search: false,
+            search_depth: 8,
+            show_ignored: false,
+            exclude: vec![".git".to_string()],
+            columns: Vec::new(),
+            preview: false,
+            preview_width: 50,
+            colors: ColorSettings::default(),
+            keys: KeySettings::default(),
+            sort_configured: false,
+        }
+    }
+}
+
+impl Default for ColorSettings {
+    fn default() -> Self {
+        Self {
+            accent: "cyan".to_string(),
+            selection: "blue".to_string(),
+            bookmark: "yellow".to_string(),
+            file: "yellow".to_string(),
+        }
+    }
+}
+
+/// `$JUMP_CONFIG`, or `config.toml` in the platform config dir (`~/.config/jump` on Linux)
+pub fn config_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("JUMP_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    directories::ProjectDirs::from("com", "jump", "jump")
+        .map(|dirs| dirs.config_dir().join(CONFIG_FILE))
+}
+
+/// Load the config file (if any) and apply `JUMP_*` overrides
+pub fn load() -> Result<Settings, SettingsError> {
+    let mut settings = match config_path() {
+        // A missing default file just means defaults; an explicit JUMP_CONFIG must exist
+        Some(path) if path.exists() || env::var_os("JUMP_CONFIG").is_some() => {
+            let content = std::fs::read_to_string(&path).map_err(|source| SettingsError::Read {
+                path: path.clone(),
+                source,
+            })?;
+            Settings::parse(&content, &path)?
+        }
+        _ => Settings::default(),
+    };
+    settings.apply_env(|name| env::var(name).ok())?;
+    Ok(settings)
+}
+
+impl Settings {
+    /// Parse and validate `content` read from `path`
+    pub fn parse(content: &str, path: &Path) -> Result<Self, SettingsError> {
+        let mut settings: Settings =
+            toml::from_str(content).map_err(|source| SettingsError::Parse {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        // Parsing succeeded above, so the file is a valid table
+        let table: toml::Table = toml::from_str(content).unwrap_or_default();
+        settings.sort_configured = table.contains_key("sort") || table.contains_key("sort_reverse");
+        for (key, _) in KEYS {
+            settings
+                .check(key)
+                .map_err(|reason| SettingsError::InvalidValue {
+                    origin: path.display().to_string(),
+                    key: key.to_string(),
+                    value: settings.get(key),
+                    reason,
+                })?;
+        }
+        settings.build_keymap(&path.display().to_string())?;
+        Ok(settings)
+    }
+
+    /// Override keys from `JUMP_*` variables looked up through `var`
+    pub fn apply_env<F>(&mut self, var: F) -> Result<(), SettingsError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        for (key, name) in KEYS {
+            if let Some(value) = var(name) {
+                self.set(key, &value)
+                    .map_err(|reason| SettingsError::InvalidValue {
+                        origin: "environment".to_string(),
+                        key: name.to_string(),
+                        value,
+                        reason,
+                    })?;
+                if key.starts_with("sort") {
+                    self.sort_configured = true;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Command-line flags only ever switch features on or off relative to the file
+    pub fn merge_cli(&mut self, config: &Config) {
+        if config.show_hidden {
+            self.show_hidden = true;
+        }
+        if config.no_stale_check {
+            self.check_stale = false;
+        }
+        if let Some(depth) = config.depth {
+            self.deep_search = true;
+            self.search_depth = depth;
+        }
+    }
+
+    pub fn theme(&self) -> Theme {
+        let default = Theme::default();
+        let color = |value: &str, fallback: Color| Color::from_str(value).unwrap_or(fallback);
+        Theme {
+            accent: color(&self.colors.accent, default.accent),
+            selection: color(&self.colors.selection, default.selection),
+            bookmark: color(&self.colors.bookmark, default.bookmark),
+            file: color(&self.colors.file, default.file),
+        }
+    }
+
+    /// Configured sort mode; the name was checked on load
+    pub fn sort(&self) -> SortMode {
+        self.sort.parse().unwrap_or_default()
+    }
+
+    /// Configured metadata columns in display order; names were checked on load
+    pub fn columns(&self) -> Vec<Column> {
+        Column::ALL
+            .into_iter()
+            .filter(|column| self.columns.iter().any(|name| name == column.name()))
+            .collect()
+    }
+
+    /// Default key bindings with the `[keys.*]` overrides applied
+    pub fn keymap(&self) -> Keymap {
+        // Overrides were validated when the file was loaded
+        self.build_keymap("config").unwrap_or_default()
+    }
+
+    fn build_keymap(&self, origin: &str) -> Result<Keymap, SettingsError> {
+        let mut keymap = Keymap::default();
+        for mode in KeymapMode::ALL {
+            for (keys, action) in self.keys.mode(mode) {
+                keymap
+                    .bind(mode, keys, action)
+                    .map_err(|reason| SettingsError::InvalidValue {
+                        origin: origin.to_string(),
+                        key: format!("keys.{}.{}", mode.name(), keys),
+                        value: action.clone(),
+                        reason,
+                    })?;
+            }
+        }
+        Ok(keymap)
+    }
+
+    /// The effective settings as TOML, for `jump config`
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).unwrap_or_default()
+    }
+
+    fn get(&self, key: &str) -> String {
+        match key {
+            "editor" => self.editor.clone(),
+            "show_hidden" => self.show_hidden.to_string(),
+            "show_files" => self.show_files.to_string(),
+            "dirs_first" => self.dirs_first.to_string(),
+            "sort" => self.sort.clone(),
+            "sort_reverse" => self.sort_reverse.to_string(),
+            "page_size" => self.page_size.to_string(),
+            "check_stale" => self.check_stale.to_string(),
+            "resolve_symlinks" => self.resolve_symlinks.to_string(),
+            "deep_search" => self.deep_search.to_string(),
+            "search_depth" => self.search_depth.to_string(),
+            "show_ignored" => self.show_ignored.to_string(),
+            "exclude" => self.exclude.join(","),
+            "columns" => self.columns.join(","),
+            "preview" => self.preview.to_string(),
+            "preview_width" => self.preview_width.to_string(),
+            "colors.accent" => self.colors.accent.clone(),
+            "colors.selection" => self.colors.selection.clone(),
+            "colors.bookmark" => self.colors.bookmark.clone(),
+            "colors.file" => self.colors.file.clone(),
+            _ => String::new(),
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "editor" => self.editor = value.to_string(),
+            "show_hidden" => self.show_hidden = parse_bool(value)?,
+            "show_files" => self.show_files = parse_bool(value)?,
+            "dirs_first" => self.dirs_first = parse_bool(value)?,
+            "sort" => self.sort = value.to_string(),
+            "sort_reverse" => self.sort_reverse = parse_bool(value)?,
+            "page_size" => {
+                self.page_size = value
+                    .parse()
+                    .map_err(|_| "expected a positive integer".to_string())?
+            }
+            "check_stale" => self.check_stale = parse_bool(value)?,
+            "resolve_symlinks" => self.resolve_symlinks = parse_bool(value)?,
+            "deep_search" => self.deep_search = parse_bool(value)?,
+            "search_depth" => {
+                self.search_depth = value
+                    .parse()
+                    .map_err(|_| "expected a positive integer".to_string())?
+            }
+            "show_ignored" => self.show_ignored = parse_bool(value)?,
+            "exclude" => self.exclude = parse_list(value),
+            "columns" => self.columns = parse_list(value),
+            "preview" => self.preview = parse_bool(value)?,
+            "preview_width" => {
+                self.preview_width = value
+                    .parse()
+                    .map_err(|_| "expected a percentage".to_string())?
+            }
+            "colors.accent" => self.colors.accent = value.to_string(),
+            "colors.selection" => self.colors.selection = value.to_string(),
+            "colors.bookmark" => self.colors.bookmark = value.to_string(),
+            "colors.file" => self.colors.file = value.to_string(),
+            _ => return Err("unknown key".to_string()),
+        }
+        self.check(key)
+    }
+
+    /// Checks that types alone can't express
+    fn check(&self, key: &str) -> Result<(), String> {
+        match key {
+            "editor" if self.editor.trim().is_empty() => Err("must not be empty".to_string()),
+            "page_size" if self.page_size == 0 => Err("must be at least 1".to_string()),
+            "search_depth" if self.search_depth == 0 => Err("must be at least 1".to_string()),
+            "preview_width" if !(10..=90).contains(&self.preview_width) => {
+                Err("must be between 10 and 90".to_string())
+            }
+            "sort" => self.sort.parse::<SortMode>().map(|_| ()),
+            "columns" => self
+                .columns
+                .iter()
+                .try_for_each(|name| name.parse::<Column>().map(|_| ())),
+            color if color.starts_with("colors.") => Color::from_str(&self.get(color))
+                .map(|_| ())
+                .map_err(|_| "expected a color name, #rrggbb or 0-255".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Comma-separated names; blanks are dropped
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err("expected true or false".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymap::{Action, Resolved};
+
+    fn parse(content: &str) -> Result<Settings, SettingsError> {
+        Settings::parse(content, Path::new("config.toml"))
+    }
+
+    #[test]
+    fn test_default_config_matches_defaults() {
+        assert_eq!(parse(DEFAULT_CONFIG).unwrap(), Settings::default());
+        assert_eq!(parse("").unwrap(), Settings::default());
+    }
+
+    #[test]
+    fn test_partial_file_keeps_other_defaults() {
+        let settings = parse("page_size = 25\n[colors]\naccent = \"#ff8800\"\n").unwrap();
+        assert_eq!(settings.page_size, 25);
+        assert_eq!(settings.theme().accent, Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(settings.editor, "vim");
+        assert_eq!(settings.theme().selection, Color::Blue);
+        assert!(!settings.sort_configured);
+        assert!(parse("sort_reverse = true\n").unwrap().sort_configured);
+    }
+
+    #[test]
+    fn test_errors_name_the_key() {
+        let err = parse("pag_size = 3\n").unwrap_err().to_string();
+        assert!(err.contains("pag_size"), "{}", err);
+        assert!(err.contains("line 1"), "{}", err);
+
+        let err = parse("page_size = \"ten\"\n").unwrap_err().to_string();
+        assert!(err.contains("page_size"), "{}", err);
+
+        let err = parse("page_size = 0\n").unwrap_err().to_string();
+        assert!(err.contains("`page_size`"), "{}", err);
+
+        let err = parse("preview_width = 95\n").unwrap_err().to_string();
+        assert!(err.contains("between 10 and 90"), "{}", err);
+
+        let err = parse("[colors]\nbookmark = \"golden\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`colors.bookmark`"), "{}", err);
+        assert!(err.contains("golden"), "{}", err);
+    }
+
+    #[test]
+    fn test_key_overrides() {
+        let settings =
+            parse("[keys.normal]\nd = \"remove_bookmark\"\n\"g g\" = \"go_to_start\"\n").unwrap();
+        assert_eq!(settings.keys.normal.len(), 2);
+        let mut keymap = settings.keymap();
+        assert_eq!(
+            keymap.feed(KeymapMode::Normal, "d".parse().unwrap()),
+            Resolved::Action(Action::RemoveBookmark)
+        );
+
+        let err = parse("[keys.normal]\nd = \"delete\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`keys.normal.d`"), "{}", err);
+        assert!(err.contains("unknown action 'delete'"), "{}", err);
+
+        let err = parse("[keys.search]\n\"ctrl-qq\" = \"cancel\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`keys.search.ctrl-qq`"), "{}", err);
+
+        let err = parse("[keys.visual]\n").unwrap_err().to_string();
+        assert!(err.contains("visual"), "{}", err);
+    }
+
+    #[test]
+    fn test_env_overrides_file() {
+        let mut settings = parse("page_size = 25\nshow_files = true\n").unwrap();
+        settings
+            .apply_env(|name| match name {
+                "JUMP_PAGE_SIZE" => Some("5".to_string()),
+                "JUMP_SHOW_FILES" => Some("off".to_string()),
+                "JUMP_COLORS_FILE" => Some("green".to_string()),
+                "JUMP_EXCLUDE" => Some("build, .cache,".to_string()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(settings.page_size, 5);
+        assert!(!settings.show_files);
+        assert_eq!(settings.theme().file, Color::Green);
+        assert_eq!(settings.exclude, ["build", ".cache"]);
+        assert!(!settings.sort_configured);
+
+        let err = settings
+            .apply_env(|name| (name == "JUMP_SHOW_HIDDEN").then(|| "maybe".to_string()))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("JUMP_SHOW_HIDDEN"), "{}", err);
+    }
+
+    #[test]
+    fn test_cli_flags_override_settings() {
+        let mut settings = Settings::default();
+        settings.merge_cli(&Config {
+            show_hidden: true,
+            no_stale_check: true,
+            depth: Some(3),
+            ..Default::default()
+        });
+        assert!(settings.show_hidden);
+        assert!(!settings.check_stale);
+        assert!(settings.deep_search);
+        assert_eq!(settings.search_depth, 3);
+    }
+}
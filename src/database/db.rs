@@ -1,85 +1,141 @@
-use anyhow::{Context, Result};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use super::entry::DirEntry;
+use anyhow::{bail, Context, Result};
+
+use super::entry::{DirEntry, VisitRecord};
+use super::migrations;
+use crate::fs;
 
 const DB_NAME: &str = "jump.db";
-const SCHEMA: &str = r#"
-CREATE TABLE IF NOT EXISTS entries (
-    id INTEGER PRIMARY KEY AUTOINCREMENT,
-    path TEXT NOT NULL UNIQUE,
-    name TEXT NOT NULL,
-    is_bookmark INTEGER NOT NULL DEFAULT 0,
-    bookmark_key TEXT
-);
-
-CREATE INDEX IF NOT EXISTS idx_path ON entries(path);
-CREATE INDEX IF NOT EXISTS idx_bookmark_key ON entries(bookmark_key) WHERE bookmark_key IS NOT NULL;
-"#;
+const ENTRY_COLUMNS: &str = "path, name, is_bookmark, bookmark_key, access_count, last_accessed";
+
+/// Current time as seconds since the Unix epoch
+pub fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// The stored spelling of `path`, so one directory never gets two rows
+///
+/// Paths must be absolute: callers resolve user input with `fs::normalize_path` against
+/// their own base. Only `.`, `..`, repeated and trailing separators are removed here.
+fn stored_path(path: &str) -> Result<String> {
+    if !Path::new(path).is_absolute() {
+        bail!("expected an absolute path, got '{}'", path);
+    }
+    Ok(fs::clean_path(Path::new(path))
+        .to_string_lossy()
+        .into_owned())
+}
+
+fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<DirEntry> {
+    Ok(DirEntry {
+        path: row.get(0)?,
+        name: row.get(1)?,
+        is_bookmark: row.get(2)?,
+        bookmark_key: row.get(3)?,
+        access_count: row.get(4)?,
+        last_accessed: row.get(5)?,
+    })
+}
 
+#[derive(Debug, thiserror::Error)]
+pub enum BookmarkError {
+    #[error("Bookmark '{key}' already points to {existing_path}")]
+    KeyConflict { key: String, existing_path: String },
+    #[error(transparent)]
+    Database(#[from] anyhow::Error),
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+}
+
+/// Bookmarks and visit history stored in `jump.db`
 pub struct Database {
     conn: rusqlite::Connection,
 }
 
 impl Database {
+    /// Open (or create) the user's database in the platform data directory
     pub fn new() -> Result<Self> {
         let binding = directories::ProjectDirs::from("com", "jump", "jump")
             .expect("Failed to get project directories");
         let data_dir = binding.data_dir();
 
         std::fs::create_dir_all(data_dir).context("Failed to create data directory")?;
-        let db_path = data_dir.join(DB_NAME);
+        Self::open(&data_dir.join(DB_NAME))
+    }
 
+    /// Open (or create) a database at an explicit path
+    pub fn open(db_path: &Path) -> Result<Self> {
         let conn = rusqlite::Connection::open(db_path)?;
         conn.pragma_update(None, "journal_mode", "WAL")?;
-        conn.execute(SCHEMA, [])?;
+        Self::init(conn)
+    }
 
+    /// A throwaway database that lives as long as the handle
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(rusqlite::Connection::open_in_memory()?)
+    }
+
+    fn init(mut conn: rusqlite::Connection) -> Result<Self> {
+        migrations::migrate(&mut conn)?;
         Ok(Self { conn })
     }
 
+    /// The entry bookmarked under `key`, if any
     pub fn get_by_bookmark_key(&self, key: &str) -> Result<Option<DirEntry>> {
         use rusqlite::OptionalExtension;
 
-        let mut stmt = self.conn.prepare(
-            "SELECT path, name, is_bookmark, bookmark_key
-             FROM entries WHERE bookmark_key = ?1",
-        )?;
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM entries WHERE bookmark_key = ?1"
+        ))?;
 
-        let result: Option<DirEntry> = stmt
-            .query_row([key], |row| {
-                Ok(DirEntry {
-                    path: row.get(0)?,
-                    name: row.get(1)?,
-                    is_bookmark: row.get(2)?,
-                    bookmark_key: row.get(3)?,
-                })
-            })
-            .optional()?;
+        let result: Option<DirEntry> = stmt.query_row([key], row_to_entry).optional()?;
 
         Ok(result)
     }
 
-    pub fn set_bookmark(&self, path: &str, name: &str, key: &str) -> Result<()> {
-        let exists: bool = self.conn.query_row(
-            "SELECT EXISTS(SELECT 1 FROM entries WHERE path = ?1)",
-            [path],
-            |row| row.get(0),
-        )?;
+    /// Assign `key` to the absolute `path`. Fails with `BookmarkError::KeyConflict` if
+    /// the key already belongs to a different path. The path is cleaned first (see
+    /// `stored_path`), so different spellings share one entry.
+    pub fn set_bookmark(&self, path: &str, name: &str, key: &str) -> Result<(), BookmarkError> {
+        let path = stored_path(path)?;
+        let path = path.as_str();
 
-        if exists {
-            self.conn.execute(
-                "UPDATE entries SET name = ?1, is_bookmark = 1, bookmark_key = ?2 WHERE path = ?3",
-                (name, key, path),
-            )?;
-        } else {
-            self.conn.execute(
-                "INSERT INTO entries (path, name, is_bookmark, bookmark_key)
-                 VALUES (?1, ?2, 1, ?3)",
-                (path, name, key),
-            )?;
+        if let Some(existing) = self.get_by_bookmark_key(key)? {
+            if existing.path != path {
+                return Err(BookmarkError::KeyConflict {
+                    key: key.to_string(),
+                    existing_path: existing.path,
+                });
+            }
         }
+
+        self.conn.execute(
+            "INSERT INTO entries (path, name, is_bookmark, bookmark_key)
+             VALUES (?1, ?2, 1, ?3)
+             ON CONFLICT(path) DO UPDATE SET
+                name = excluded.name,
+                is_bookmark = 1,
+                bookmark_key = excluded.bookmark_key",
+            (path, name, key),
+        )?;
         Ok(())
     }
 
+    /// Assign `key` to `path`, taking it over from any other path that holds it
+    pub fn overwrite_bookmark(&self, path: &str, name: &str, key: &str) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        self.remove_bookmark(key)?;
+        self.set_bookmark(path, name, key)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Clear the bookmark `key`; the entry's visit history is kept
     pub fn remove_bookmark(&self, key: &str) -> Result<()> {
         self.conn.execute(
             "UPDATE entries SET is_bookmark = 0, bookmark_key = NULL WHERE bookmark_key = ?1",
@@ -88,24 +144,358 @@ impl Database {
         Ok(())
     }
 
+    /// Apply a batch of `(path, name, key)` bookmarks in one transaction.
+    /// With `replace`, all existing bookmarks are cleared first; otherwise an
+    /// imported key takes over from whatever path held it before.
+    pub fn import_bookmarks(
+        &self,
+        bookmarks: &[(String, String, String)],
+        replace: bool,
+    ) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        if replace {
+            tx.execute(
+                "UPDATE entries SET is_bookmark = 0, bookmark_key = NULL WHERE is_bookmark = 1",
+                [],
+            )?;
+        }
+        for (path, name, key) in bookmarks {
+            self.remove_bookmark(key)?;
+            self.set_bookmark(path, name, key)?;
+        }
+        tx.commit()?;
+        Ok(bookmarks.len())
+    }
+
+    /// All bookmarks, ordered by key
     pub fn get_all_bookmarks(&self) -> Result<Vec<DirEntry>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT path, name, is_bookmark, bookmark_key
-             FROM entries WHERE is_bookmark = 1 ORDER BY bookmark_key",
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM entries WHERE is_bookmark = 1 ORDER BY bookmark_key"
+        ))?;
+
+        let entries = stmt
+            .query_map([], row_to_entry)?
+            .filter_map(|e| e.ok())
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Record a visit to the absolute `path`, creating the entry if it is not yet known
+    pub fn record_visit(&self, path: &str) -> Result<()> {
+        self.record_visit_at(path, now_secs())
+    }
+
+    fn record_visit_at(&self, path: &str, timestamp: i64) -> Result<()> {
+        let path = stored_path(path)?;
+        let path = path.as_str();
+        let name = Path::new(path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(path);
+
+        self.conn.execute(
+            "INSERT INTO entries (path, name, access_count, last_accessed)
+             VALUES (?1, ?2, 1, ?3)
+             ON CONFLICT(path) DO UPDATE SET
+                access_count = access_count + 1,
+                last_accessed = excluded.last_accessed",
+            (path, name, timestamp),
         )?;
+        Ok(())
+    }
+
+    /// Upsert imported visit history in one transaction, returning the number of rows written.
+    /// Existing entries keep the larger of the stored and imported counts and timestamps.
+    pub fn import_visits(&self, visits: &[VisitRecord]) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO entries (path, name, access_count, last_accessed)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(path) DO UPDATE SET
+                    access_count = MAX(access_count, excluded.access_count),
+                    last_accessed = MAX(COALESCE(last_accessed, 0), excluded.last_accessed)",
+            )?;
+            for visit in visits {
+                let path = stored_path(&visit.path)?;
+                let name = Path::new(&path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or(&path);
+                stmt.execute((&path, name, visit.access_count, visit.last_accessed))?;
+            }
+        }
+        tx.commit()?;
+        Ok(visits.len())
+    }
+
+    /// Every stored entry, bookmarked or visited
+    pub fn get_all_entries(&self) -> Result<Vec<DirEntry>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM entries ORDER BY path"
+        ))?;
 
         let entries = stmt
-            .query_map([], |row| {
-                Ok(DirEntry {
-                    path: row.get(0)?,
-                    name: row.get(1)?,
-                    is_bookmark: row.get(2)?,
-                    bookmark_key: row.get(3)?,
-                })
-            })?
+            .query_map([], row_to_entry)?
+            .filter_map(|e| e.ok())
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Entries whose path no longer exists as a directory
+    pub fn find_stale(&self) -> Result<Vec<DirEntry>> {
+        Ok(self
+            .get_all_entries()?
+            .into_iter()
+            .filter(|e| !fs::is_accessible(Path::new(&e.path)))
+            .collect())
+    }
+
+    /// Delete entries (bookmarks and visit history) for the given paths
+    pub fn remove_entries(&self, paths: &[String]) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut removed = 0;
+        for path in paths {
+            removed += tx.execute("DELETE FROM entries WHERE path = ?1", [path])?;
+        }
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// A value saved with `set_state`
+    pub fn get_state(&self, key: &str) -> Result<Option<String>> {
+        use rusqlite::OptionalExtension;
+
+        Ok(self
+            .conn
+            .query_row("SELECT value FROM state WHERE key = ?1", [key], |row| {
+                row.get(0)
+            })
+            .optional()?)
+    }
+
+    /// Save `value` under `key`, replacing any earlier value
+    pub fn set_state(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO state (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            [key, value],
+        )?;
+        Ok(())
+    }
+
+    /// Get visited entries ranked by frecency, highest first
+    pub fn get_frecent(&self, limit: usize) -> Result<Vec<DirEntry>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM entries WHERE access_count > 0"
+        ))?;
+
+        let mut entries: Vec<DirEntry> = stmt
+            .query_map([], row_to_entry)?
             .filter_map(|e| e.ok())
             .collect();
 
+        let now = now_secs();
+        entries.sort_by(|a, b| {
+            b.frecency(now)
+                .total_cmp(&a.frecency(now))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        entries.truncate(limit);
+
         Ok(entries)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_visit_creates_and_increments() {
+        let db = Database::open_in_memory().unwrap();
+        db.record_visit("/home/user/projects").unwrap();
+        db.record_visit("/home/user/projects").unwrap();
+
+        let frecent = db.get_frecent(10).unwrap();
+        assert_eq!(frecent.len(), 1);
+        assert_eq!(frecent[0].name, "projects");
+        assert_eq!(frecent[0].access_count, 2);
+        assert!(!frecent[0].is_bookmark);
+    }
+
+    #[test]
+    fn test_frecent_prefers_recent_visits() {
+        let db = Database::open_in_memory().unwrap();
+        let now = now_secs();
+        for _ in 0..3 {
+            db.record_visit_at("/old", now - 30 * 24 * 60 * 60).unwrap();
+        }
+        db.record_visit_at("/recent", now).unwrap();
+
+        let frecent = db.get_frecent(10).unwrap();
+        assert_eq!(frecent[0].path, "/recent");
+        assert_eq!(frecent[1].path, "/old");
+    }
+
+    #[test]
+    fn test_import_visits_keeps_larger_count() {
+        let db = Database::open_in_memory().unwrap();
+        for _ in 0..5 {
+            db.record_visit("/work").unwrap();
+        }
+
+        let visits = vec![
+            VisitRecord {
+                path: "/work".to_string(),
+                access_count: 2,
+                last_accessed: 0,
+            },
+            VisitRecord {
+                path: "/srv/app".to_string(),
+                access_count: 9,
+                last_accessed: 100,
+            },
+        ];
+        assert_eq!(db.import_visits(&visits).unwrap(), 2);
+
+        let frecent = db.get_frecent(10).unwrap();
+        let work = frecent.iter().find(|e| e.path == "/work").unwrap();
+        let app = frecent.iter().find(|e| e.path == "/srv/app").unwrap();
+        assert_eq!(work.access_count, 5);
+        assert_eq!(app.access_count, 9);
+        assert_eq!(app.name, "app");
+    }
+
+    #[test]
+    fn test_import_bookmarks_merge_and_replace() {
+        let db = Database::open_in_memory().unwrap();
+        db.set_bookmark("/old", "old", "o").unwrap();
+        db.set_bookmark("/work", "work", "w").unwrap();
+
+        let incoming = vec![("/new-work".to_string(), "work".to_string(), "w".to_string())];
+        db.import_bookmarks(&incoming, false).unwrap();
+        let keys: Vec<_> = db
+            .get_all_bookmarks()
+            .unwrap()
+            .into_iter()
+            .map(|b| (b.bookmark_key.unwrap(), b.path))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("o".to_string(), "/old".to_string()),
+                ("w".to_string(), "/new-work".to_string())
+            ]
+        );
+
+        db.import_bookmarks(&incoming, true).unwrap();
+        assert_eq!(db.get_all_bookmarks().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_set_bookmark_key_conflict() {
+        let db = Database::open_in_memory().unwrap();
+        db.set_bookmark("/work", "work", "w").unwrap();
+        // Re-assigning the same key to the same path is fine
+        db.set_bookmark("/work", "work", "w").unwrap();
+
+        let err = db.set_bookmark("/www", "www", "w").unwrap_err();
+        match err {
+            BookmarkError::KeyConflict { key, existing_path } => {
+                assert_eq!(key, "w");
+                assert_eq!(existing_path, "/work");
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+
+        db.overwrite_bookmark("/www", "www", "w").unwrap();
+        let bookmarks = db.get_all_bookmarks().unwrap();
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].path, "/www");
+    }
+
+    #[test]
+    fn test_set_bookmark_normalizes_path() {
+        let db = Database::open_in_memory().unwrap();
+        db.set_bookmark("/srv/app/../web/", "web", "w").unwrap();
+
+        let bookmark = db.get_by_bookmark_key("w").unwrap().unwrap();
+        assert_eq!(bookmark.path, "/srv/web");
+
+        // Another spelling of the same directory is not a conflict
+        db.set_bookmark("/srv/./web", "web", "w").unwrap();
+        assert_eq!(db.get_all_bookmarks().unwrap().len(), 1);
+
+        // Relative paths are the caller's to resolve
+        assert!(db.set_bookmark("web", "web", "x").is_err());
+        assert!(db.get_by_bookmark_key("x").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_visits_share_one_row_per_directory() {
+        let db = Database::open_in_memory().unwrap();
+        db.record_visit("/srv/web/").unwrap();
+        db.record_visit("/srv/app/../web").unwrap();
+        let visits = vec![VisitRecord {
+            path: "/srv//web/.".to_string(),
+            access_count: 7,
+            last_accessed: 0,
+        }];
+        db.import_visits(&visits).unwrap();
+
+        let entries = db.get_all_entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, "/srv/web");
+        assert_eq!(entries[0].name, "web");
+        assert_eq!(entries[0].access_count, 7);
+        assert!(db.record_visit("web").is_err());
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let db = Database::open_in_memory().unwrap();
+        assert_eq!(db.get_state("sort").unwrap(), None);
+        db.set_state("sort", "size").unwrap();
+        db.set_state("sort", "modified").unwrap();
+        assert_eq!(db.get_state("sort").unwrap().as_deref(), Some("modified"));
+    }
+
+    #[test]
+    fn test_find_and_remove_stale() {
+        let db = Database::open_in_memory().unwrap();
+        let existing = std::env::temp_dir().to_string_lossy().into_owned();
+        db.set_bookmark(&existing, "tmp", "t").unwrap();
+        db.set_bookmark("/nonexistent/jump/gone", "gone", "g")
+            .unwrap();
+        db.record_visit("/nonexistent/jump/old").unwrap();
+
+        let stale: Vec<String> = db
+            .find_stale()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.path)
+            .collect();
+        assert_eq!(
+            stale,
+            vec!["/nonexistent/jump/gone", "/nonexistent/jump/old"]
+        );
+
+        assert_eq!(db.remove_entries(&stale).unwrap(), 2);
+        assert!(db.find_stale().unwrap().is_empty());
+        assert_eq!(db.get_all_bookmarks().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_record_visit_keeps_bookmark() {
+        let db = Database::open_in_memory().unwrap();
+        db.set_bookmark("/work", "work", "w").unwrap();
+        db.record_visit("/work").unwrap();
+
+        let bookmark = db.get_by_bookmark_key("w").unwrap().unwrap();
+        assert_eq!(bookmark.access_count, 1);
+        assert!(bookmark.is_bookmark);
+    }
+}
@@ -1,7 +1,45 @@
+/// Seconds in an hour, used for frecency recency buckets
+const HOUR: i64 = 60 * 60;
+const DAY: i64 = 24 * HOUR;
+const WEEK: i64 = 7 * DAY;
+
+/// A stored path: a bookmark, a visited directory, or both
 #[derive(Debug, Clone, PartialEq)]
 pub struct DirEntry {
     pub path: String,
     pub name: String,
     pub is_bookmark: bool,
     pub bookmark_key: Option<String>,
+    pub access_count: i64,
+    pub last_accessed: Option<i64>,
+}
+
+impl DirEntry {
+    /// Frecency score: visit count weighted by how recently the path was visited
+    pub fn frecency(&self, now: i64) -> f64 {
+        let Some(last) = self.last_accessed else {
+            return 0.0;
+        };
+
+        let age = now.saturating_sub(last);
+        let weight = if age < HOUR {
+            4.0
+        } else if age < DAY {
+            2.0
+        } else if age < WEEK {
+            0.5
+        } else {
+            0.25
+        };
+
+        self.access_count as f64 * weight
+    }
+}
+
+/// Visit history for a single path, as imported from another jumper
+#[derive(Debug, Clone, PartialEq)]
+pub struct VisitRecord {
+    pub path: String,
+    pub access_count: i64,
+    pub last_accessed: i64,
 }
@@ -0,0 +1,361 @@
+//! Versioned schema migrations for jump.db
+//!
+//! The applied version is tracked in `PRAGMA user_version`. Pending migrations
+//! run in order inside a single transaction when the database is opened.
+
+use std::path::Path;
+
+use rusqlite::{Connection, OptionalExtension, Transaction};
+
+use crate::fs;
+
+/// A single ordered up-migration
+struct Migration {
+    version: u32,
+    up: fn(&Transaction) -> rusqlite::Result<()>,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        up: create_entries,
+    },
+    Migration {
+        version: 2,
+        up: add_visit_columns,
+    },
+    Migration {
+        version: 3,
+        up: unique_bookmark_keys,
+    },
+    Migration {
+        version: 4,
+        up: normalize_paths,
+    },
+    Migration {
+        version: 5,
+        up: create_state,
+    },
+];
+
+/// Latest schema version this build knows how to use
+pub const SCHEMA_VERSION: u32 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error(
+        "Database schema version {found} is newer than supported version {supported} - upgrade jump to open it"
+    )]
+    TooNew { found: u32, supported: u32 },
+    #[error("Migration to schema version {version} failed: {source}")]
+    Failed {
+        version: u32,
+        #[source]
+        source: rusqlite::Error,
+    },
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+}
+
+/// Read the schema version stored in the database header
+pub fn current_version(conn: &Connection) -> rusqlite::Result<u32> {
+    conn.pragma_query_value(None, "user_version", |row| row.get(0))
+}
+
+/// Bring the database up to `SCHEMA_VERSION`, refusing databases from newer releases
+pub fn migrate(conn: &mut Connection) -> Result<(), MigrationError> {
+    let found = current_version(conn)?;
+    if found > SCHEMA_VERSION {
+        return Err(MigrationError::TooNew {
+            found,
+            supported: SCHEMA_VERSION,
+        });
+    }
+    if found == SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    let tx = conn.transaction()?;
+    for migration in MIGRATIONS.iter().filter(|m| m.version > found) {
+        (migration.up)(&tx).map_err(|source| MigrationError::Failed {
+            version: migration.version,
+            source,
+        })?;
+        tx.pragma_update(None, "user_version", migration.version)?;
+    }
+    tx.commit()?;
+
+    Ok(())
+}
+
+fn has_column(tx: &Transaction, table: &str, column: &str) -> rusqlite::Result<bool> {
+    tx.query_row(
+        "SELECT EXISTS(SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2)",
+        [table, column],
+        |row| row.get(0),
+    )
+}
+
+/// v1: the bookmark-only schema shipped in 1.1.0
+fn create_entries(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS entries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            path TEXT NOT NULL UNIQUE,
+            name TEXT NOT NULL,
+            is_bookmark INTEGER NOT NULL DEFAULT 0,
+            bookmark_key TEXT
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_path ON entries(path);
+        CREATE INDEX IF NOT EXISTS idx_bookmark_key ON entries(bookmark_key) WHERE bookmark_key IS NOT NULL;
+        "#,
+    )
+}
+
+/// v2: visit tracking for frecency. Databases from 1.0.0 may still carry these columns.
+fn add_visit_columns(tx: &Transaction) -> rusqlite::Result<()> {
+    if !has_column(tx, "entries", "access_count")? {
+        tx.execute(
+            "ALTER TABLE entries ADD COLUMN access_count INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
+    if !has_column(tx, "entries", "last_accessed")? {
+        tx.execute("ALTER TABLE entries ADD COLUMN last_accessed INTEGER", [])?;
+    }
+    Ok(())
+}
+
+/// v3: bookmark keys are unique. Older databases may hold duplicates; the
+/// most recently created entry keeps the key.
+fn unique_bookmark_keys(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        r#"
+        UPDATE entries SET is_bookmark = 0, bookmark_key = NULL
+        WHERE bookmark_key IS NOT NULL
+          AND id NOT IN (
+              SELECT MAX(id) FROM entries WHERE bookmark_key IS NOT NULL GROUP BY bookmark_key
+          );
+
+        DROP INDEX IF EXISTS idx_bookmark_key;
+        CREATE UNIQUE INDEX idx_bookmark_key ON entries(bookmark_key) WHERE bookmark_key IS NOT NULL;
+        "#,
+    )
+}
+
+/// v4: stored paths are normalized. Rows that turn out to be the same
+/// directory are merged, summing visits and keeping the bookmark key. When
+/// both rows carry a key, the later row is left under its old spelling so
+/// neither bookmark is lost.
+fn normalize_paths(tx: &Transaction) -> rusqlite::Result<()> {
+    let rows: Vec<(i64, String)> = {
+        let mut stmt = tx.prepare("SELECT id, path FROM entries ORDER BY id")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+
+    for (id, path) in rows {
+        // Relative paths cannot be resolved without the cwd they were stored from
+        if !Path::new(&fs::expand_path(&path)).is_absolute() {
+            continue;
+        }
+        let normalized = fs::normalize_path(&path, Path::new("/"), false)
+            .to_string_lossy()
+            .into_owned();
+        if normalized == path {
+            continue;
+        }
+
+        let existing: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM entries WHERE path = ?1",
+                [&normalized],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        match existing {
+            Some(keep) => {
+                let (key, count, last): (Option<String>, i64, Option<i64>) = tx.query_row(
+                    "SELECT bookmark_key, access_count, last_accessed FROM entries WHERE id = ?1",
+                    [id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )?;
+                let kept_key: Option<String> = tx.query_row(
+                    "SELECT bookmark_key FROM entries WHERE id = ?1",
+                    [keep],
+                    |row| row.get(0),
+                )?;
+                if key.is_some() && kept_key.is_some() {
+                    continue;
+                }
+                tx.execute("DELETE FROM entries WHERE id = ?1", [id])?;
+                tx.execute(
+                    "UPDATE entries SET
+                        access_count = access_count + ?2,
+                        last_accessed = MAX(COALESCE(last_accessed, 0), COALESCE(?3, 0)),
+                        bookmark_key = COALESCE(bookmark_key, ?4),
+                        is_bookmark = bookmark_key IS NOT NULL OR ?4 IS NOT NULL
+                     WHERE id = ?1",
+                    (keep, count, last, key),
+                )?;
+            }
+            None => {
+                tx.execute(
+                    "UPDATE entries SET path = ?1 WHERE id = ?2",
+                    (&normalized, id),
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// v5: picker state kept between runs, such as the sort
+fn create_state(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS state (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+        "#,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_fresh_database() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        // Running again is a no-op
+        migrate(&mut conn).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_migrate_legacy_database() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE entries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                path TEXT NOT NULL UNIQUE,
+                name TEXT NOT NULL,
+                is_bookmark INTEGER NOT NULL DEFAULT 0,
+                bookmark_key TEXT
+            );
+            INSERT INTO entries (path, name, is_bookmark, bookmark_key) VALUES ('/w', 'w', 1, 'w');
+            INSERT INTO entries (path, name, is_bookmark, bookmark_key) VALUES ('/w2', 'w2', 1, 'w');",
+        )
+        .unwrap();
+
+        migrate(&mut conn).unwrap();
+
+        let count: i64 = conn
+            .query_row(
+                "SELECT access_count FROM entries WHERE path = '/w'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(count, 0);
+
+        // Duplicate keys collapse onto the newest entry
+        let owner: String = conn
+            .query_row(
+                "SELECT path FROM entries WHERE bookmark_key = 'w'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(owner, "/w2");
+    }
+
+    #[test]
+    fn test_migrate_normalizes_and_merges_paths() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE entries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                path TEXT NOT NULL UNIQUE,
+                name TEXT NOT NULL,
+                is_bookmark INTEGER NOT NULL DEFAULT 0,
+                bookmark_key TEXT
+            );
+            INSERT INTO entries (path, name) VALUES ('/srv/app', 'app');
+            INSERT INTO entries (path, name, is_bookmark, bookmark_key) VALUES ('/srv/x/../app/', 'app', 1, 'a');
+            INSERT INTO entries (path, name, is_bookmark, bookmark_key) VALUES ('/opt/./tools', 'tools', 1, 't');
+            INSERT INTO entries (path, name) VALUES ('relative/dir', 'dir');",
+        )
+        .unwrap();
+
+        migrate(&mut conn).unwrap();
+
+        let rows: Vec<(String, Option<String>)> = conn
+            .prepare("SELECT path, bookmark_key FROM entries ORDER BY path")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("/opt/tools".to_string(), Some("t".to_string())),
+                ("/srv/app".to_string(), Some("a".to_string())),
+                ("relative/dir".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_migrate_keeps_both_bookmark_keys() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE entries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                path TEXT NOT NULL UNIQUE,
+                name TEXT NOT NULL,
+                is_bookmark INTEGER NOT NULL DEFAULT 0,
+                bookmark_key TEXT
+            );
+            INSERT INTO entries (path, name, is_bookmark, bookmark_key) VALUES ('/srv/app', 'app', 1, 'a');
+            INSERT INTO entries (path, name, is_bookmark, bookmark_key) VALUES ('/srv/app/', 'app', 1, 'b');",
+        )
+        .unwrap();
+
+        migrate(&mut conn).unwrap();
+
+        let rows: Vec<(String, Option<String>)> = conn
+            .prepare("SELECT path, bookmark_key FROM entries ORDER BY path")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("/srv/app".to_string(), Some("a".to_string())),
+                ("/srv/app/".to_string(), Some("b".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_migrate_refuses_newer_database() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+
+        let err = migrate(&mut conn).unwrap_err();
+        assert!(matches!(err, MigrationError::TooNew { .. }));
+    }
+}
@@ -1,4 +1,7 @@
+//! SQLite storage for bookmarks and visit history
+
 pub mod db;
 pub mod entry;
+pub mod migrations;
 
-pub use db::Database;
+pub use db::{BookmarkError, Database};
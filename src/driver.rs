@@ -0,0 +1,776 @@
+//! Runs an `App`: loads its state, carries out the effects it returns, and drives it
+//! from the terminal (`pick_directory`) or from a key script on ratatui's `TestBackend`
+
+use anyhow::{Context, Result};
+use crossterm::{
+    event::{KeyCode, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::{CrosstermBackend, TestBackend},
+    buffer::Buffer,
+    Terminal,
+};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Once};
+use std::{io, panic, thread};
+use thiserror::Error;
+
+use crate::app::{App, Effect, Effects};
+use crate::config::settings::Settings;
+use crate::database::{BookmarkError, Database};
+use crate::input;
+use crate::keymap::{Key, Keymap};
+use crate::ui::{self, FuzzyState, SortMode};
+
+/// How many remembered directories the TUI searches alongside the listing
+pub const FRECENT_LIMIT: usize = 200;
+
+/// Screen size used by `jump --script`
+pub const SCRIPT_SIZE: (u16, u16) = (80, 24);
+
+/// Database state keys for the sort the picker last exited with
+const SORT_STATE: &str = "sort";
+const SORT_REVERSE_STATE: &str = "sort_reverse";
+
+/// What the event loop does after a batch of effects
+#[derive(Debug, PartialEq)]
+pub enum Flow {
+    Continue,
+    /// Leave the TUI, printing the selected path if there is one
+    Exit(Option<String>),
+}
+
+/// Build the TUI for `start_dir` with bookmarks and remembered directories from `db`
+///
+/// The sort the picker last exited with, saved in `db`, replaces the configured one.
+pub fn load_app(
+    start_dir: &Path,
+    settings: &Settings,
+    db: Option<&Database>,
+    query: Option<&str>,
+) -> App {
+    let mut state = FuzzyState::new_in_dir(start_dir, &restore_sort(settings, db));
+    if let Some(db) = db {
+        if let Ok(bookmarks) = db.get_all_bookmarks() {
+            state.set_bookmarks(bookmarks);
+        }
+        if let Ok(frecent) = db.get_frecent(FRECENT_LIMIT) {
+            state.set_frecent(frecent);
+        }
+    }
+    if let Some(q) = query {
+        state.set_query(q);
+    }
+    App::new(state)
+}
+
+/// `settings` with the sort saved by `save_sort`, if there is one and neither the
+/// config file nor the environment sets the sort
+fn restore_sort(settings: &Settings, db: Option<&Database>) -> Settings {
+    let mut settings = settings.clone();
+    if let Some(db) = db.filter(|_| !settings.sort_configured) {
+        if let Ok(Some(sort)) = db.get_state(SORT_STATE) {
+            if sort.parse::<SortMode>().is_ok() {
+                settings.sort = sort;
+            }
+        }
+        if let Ok(Some(reverse)) = db.get_state(SORT_REVERSE_STATE) {
+            settings.sort_reverse = reverse == "true";
+        }
+    }
+    settings
+}
+
+/// Remember the sort for the next run
+fn save_sort(state: &FuzzyState, db: Option<&Database>) {
+    if let Some(db) = db {
+        let _ = db.set_state(SORT_STATE, state.sort.name());
+        let _ = db.set_state(SORT_REVERSE_STATE, &state.sort_reverse.to_string());
+    }
+}
+
+/// Options for `pick_directory`
+#[derive(Debug, Clone)]
+pub struct PickOptions {
+    /// Defaults to the built-in settings; use `config::settings::load` for the user's
+    pub settings: Settings,
+    /// Initial search query
+    pub query: Option<String>,
+    /// Show bookmarks and history from jump's database and record the picked directory
+    pub use_database: bool,
+}
+
+impl Default for PickOptions {
+    fn default() -> Self {
+        Self {
+            settings: Settings::default(),
+            query: None,
+            use_database: true,
+        }
+    }
+}
+
+/// Open the navigator on `/dev/tty` at `start` and return the entry the user picked
+///
+/// Returns `None` when the user quits without picking. The terminal is restored before
+/// returning, including on error.
+pub fn pick_directory(start: &Path, options: &PickOptions) -> Result<Option<PathBuf>> {
+    let db = if options.use_database {
+        Database::new().ok()
+    } else {
+        None
+    };
+
+    setup_panic_hook();
+
+    let tty = File::options()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .context("Failed to open /dev/tty - are you running in a terminal?")?;
+    let mut tty_output = tty.try_clone()?;
+
+    enable_raw_mode()?;
+    execute!(tty_output, EnterAlternateScreen)?;
+
+    let mut terminal = Terminal::new(CrosstermBackend::new(tty_output))?;
+    let selection = run_tui(&mut terminal, start, options, db.as_ref());
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    Ok(selection?.map(PathBuf::from))
+}
+
+fn run_tui(
+    terminal: &mut Terminal<CrosstermBackend<File>>,
+    start: &Path,
+    options: &PickOptions,
+    db: Option<&Database>,
+) -> Result<Option<String>> {
+    let settings = &options.settings;
+    let mut app = load_app(start, settings, db, options.query.as_deref());
+    let mut stale_check = None;
+    if db.is_some() && settings.check_stale {
+        let paths = app
+            .state
+            .bookmarks
+            .iter()
+            .chain(app.state.frecent.iter())
+            .map(|e| e.path.clone())
+            .collect();
+        stale_check = Some(spawn_stale_check(paths));
+    }
+
+    let mut keymap = settings.keymap();
+
+    loop {
+        if let Some(missing) = stale_check.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            app.state.set_stale_paths(missing);
+            stale_check = None;
+        }
+        app.state.poll_watch();
+        app.state.poll_scan();
+        app.state.poll_preview();
+
+        terminal.draw(|f| ui::draw_fuzzy(f, &app.state, &app.mode))?;
+
+        let event = input::read_key(&mut keymap, app.mode.keymap_mode(), 100)?;
+        let effects = app.update(event);
+        if let Flow::Exit(selection) = perform(&mut app, db, effects) {
+            return Ok(selection);
+        }
+    }
+}
+
+/// Check bookmark targets off the UI thread; the receiver yields the missing paths once
+fn spawn_stale_check(paths: Vec<String>) -> mpsc::Receiver<Vec<String>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let missing = paths
+            .into_iter()
+            .filter(|p| !crate::fs::is_accessible(Path::new(p)))
+            .collect();
+        let _ = tx.send(missing);
+    });
+    rx
+}
+
+/// Leave raw mode before a panic message is printed; installed once per process
+fn setup_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let original_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |panic_info| {
+            let _ = disable_raw_mode();
+            let _ = execute!(io::stdout(), LeaveAlternateScreen);
+            original_hook(panic_info);
+        }));
+    });
+}
+
+/// Carry out the effects of one `App::update`
+pub fn perform(app: &mut App, db: Option<&Database>, effects: Effects) -> Flow {
+    for effect in effects {
+        match effect {
+            Effect::Exit => {
+                save_sort(&app.state, db);
+                return Flow::Exit(None);
+            }
+            Effect::Select(path) => {
+                if let Some(db) = db {
+                    if Path::new(&path).is_dir() {
+                        let _ = db.record_visit(&path);
+                    }
+                }
+                save_sort(&app.state, db);
+                return Flow::Exit(Some(path));
+            }
+            Effect::SetBookmark { path, name, key } => {
+                if let Some(db) = db {
+                    match db.set_bookmark(&path, &name, &key) {
+                        Ok(()) => {}
+                        Err(BookmarkError::KeyConflict { existing_path, .. }) => {
+                            app.confirm_overwrite(&key, existing_path);
+                        }
+                        Err(e) => app.state.status = Some(format!("bookmark failed: {e}")),
+                    }
+                    reload_bookmarks(db, &mut app.state);
+                }
+            }
+            Effect::OverwriteBookmark { path, name, key } => {
+                if let Some(db) = db {
+                    if let Err(e) = db.overwrite_bookmark(&path, &name, &key) {
+                        app.state.status = Some(format!("bookmark failed: {e}"));
+                    }
+                    reload_bookmarks(db, &mut app.state);
+                }
+            }
+            Effect::RemoveBookmark { key } => {
+                if let Some(db) = db {
+                    if let Err(e) = db.remove_bookmark(&key) {
+                        app.state.status = Some(format!("remove failed: {e}"));
+                    }
+                    reload_bookmarks(db, &mut app.state);
+                }
+            }
+            Effect::ForgetEntry { path } => {
+                if let Some(db) = db {
+                    if let Err(e) = db.remove_entries(&[path]) {
+                        app.state.status = Some(format!("forget failed: {e}"));
+                    }
+                    reload_entries(db, &mut app.state);
+                }
+            }
+            Effect::RepointBookmark { key, from, to } => {
+                let Some(db) = db else { continue };
+                if !Path::new(&to).is_dir() {
+                    app.state.status = Some(format!("repoint failed: {to} is not a directory"));
+                    continue;
+                }
+                let name = Path::new(&to)
+                    .file_name()
+                    .map_or_else(|| to.clone(), |n| n.to_string_lossy().into_owned());
+                let repointed = db
+                    .overwrite_bookmark(&to, &name, &key)
+                    .and_then(|()| db.remove_entries(&[from]).map(|_| ()));
+                if let Err(e) = repointed {
+                    app.state.status = Some(format!("repoint failed: {e}"));
+                }
+                reload_entries(db, &mut app.state);
+            }
+            Effect::CreateEntry { path, is_dir } => {
+                if let Err(e) = create_entry(&path, is_dir) {
+                    app.state.status = Some(format!("create failed: {e}"));
+                }
+                app.state.reload();
+            }
+        }
+    }
+    Flow::Continue
+}
+
+fn reload_bookmarks(db: &Database, state: &mut FuzzyState) {
+    if let Ok(bookmarks) = db.get_all_bookmarks() {
+        state.set_bookmarks(bookmarks);
+        state.refilter();
+    }
+}
+
+/// Reload bookmarks and remembered directories after rows were removed or moved
+fn reload_entries(db: &Database, state: &mut FuzzyState) {
+    if let Ok(frecent) = db.get_frecent(FRECENT_LIMIT) {
+        state.set_frecent(frecent);
+    }
+    reload_bookmarks(db, state);
+}
+
+/// Create a directory, or an empty file along with any missing parent directories
+fn create_entry(target: &Path, is_dir: bool) -> io::Result<()> {
+    if is_dir {
+        return std::fs::create_dir_all(target);
+    }
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::File::create(target).map(|_| ())
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ScriptError {
+    #[error("unclosed '<' in key script: {0}")]
+    Unclosed(String),
+    #[error("invalid key '<{token}>' in script: {reason}")]
+    InvalidKey { token: String, reason: String },
+}
+
+/// Parse a key script such as `3j l /src <enter>`
+///
+/// Each character is one key press and whitespace is ignored; named keys go in angle
+/// brackets using the keymap syntax (`<enter>`, `<ctrl-w>`, `<space>`, `<lt>` for `<`).
+pub fn parse_script(script: &str) -> Result<Vec<Key>, ScriptError> {
+    let mut keys = Vec::new();
+    let mut rest = script;
+    while let Some(c) = rest.chars().next() {
+        if c == '<' {
+            let end = rest
+                .find('>')
+                .ok_or_else(|| ScriptError::Unclosed(rest.to_string()))?;
+            let token = &rest[1..end];
+            let key = if token.eq_ignore_ascii_case("lt") {
+                Key::new(KeyCode::Char('<'), KeyModifiers::NONE)
+            } else {
+                token.parse().map_err(|reason| ScriptError::InvalidKey {
+                    token: token.to_string(),
+                    reason,
+                })?
+            };
+            keys.push(key);
+            rest = &rest[end + 1..];
+        } else {
+            if !c.is_whitespace() {
+                keys.push(Key::new(KeyCode::Char(c), KeyModifiers::NONE));
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    Ok(keys)
+}
+
+/// Result of a headless run
+#[derive(Debug)]
+pub struct ScriptOutcome {
+    /// Path that would have been printed for the shell wrapper
+    pub selection: Option<String>,
+    /// Last frame drawn, one line per row with trailing spaces trimmed
+    pub screen: String,
+}
+
+/// Feed `keys` through `keymap` into `app`, drawing each frame on a `TestBackend`
+///
+/// Stops when the app exits or the keys run out; a chord left pending at the end
+/// resolves as if it had timed out. Each key waits for a running scan and the selected
+/// entry's preview so runs are repeatable.
+pub fn run_script(
+    app: &mut App,
+    keymap: &mut Keymap,
+    db: Option<&Database>,
+    keys: &[Key],
+    (width, height): (u16, u16),
+) -> Result<ScriptOutcome> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    let mut keys = keys.iter();
+    let mut selection = None;
+
+    loop {
+        app.state.wait_for_scan();
+        app.state.wait_for_preview();
+        terminal.draw(|f| ui::draw_fuzzy(f, &app.state, &app.mode))?;
+
+        let mode = app.mode.keymap_mode();
+        let resolved = match keymap.take_replay(mode) {
+            Some(resolved) => resolved,
+            None => match keys.next() {
+                Some(key) => keymap.feed(mode, *key),
+                None => match keymap.flush_pending() {
+                    Some(resolved) => resolved,
+                    None => break,
+                },
+            },
+        };
+        let effects = app.update(resolved.into());
+        if let Flow::Exit(path) = perform(app, db, effects) {
+            selection = path;
+            break;
+        }
+    }
+
+    Ok(ScriptOutcome {
+        selection,
+        screen: screen_text(terminal.backend().buffer()),
+    })
+}
+
+fn screen_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    (area.top()..area.bottom())
+        .map(|y| {
+            let row: String = (area.left()..area.right())
+                .map(|x| buffer[(x, y)].symbol())
+                .collect();
+            row.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// A directory holding `alpha`, `beta` and `gamma/src`
+    fn tree(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("jump_test_script_{}", name));
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["alpha", "beta", "gamma/src"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        root
+    }
+
+    fn run(root: &Path, db: Option<&Database>, script: &str) -> ScriptOutcome {
+        let settings = Settings::default();
+        let mut app = load_app(root, &settings, db, None);
+        let keys = parse_script(script).unwrap();
+        run_script(&mut app, &mut settings.keymap(), db, &keys, SCRIPT_SIZE).unwrap()
+    }
+
+    #[test]
+    fn test_parse_script() {
+        let keys = parse_script("3j l /src <Enter>").unwrap();
+        assert_eq!(keys.len(), 8);
+        assert_eq!(keys[0], "3".parse().unwrap());
+        assert_eq!(keys[7], "enter".parse().unwrap());
+
+        let keys = parse_script("<ctrl-w><space><lt>").unwrap();
+        assert_eq!(keys[0], "ctrl-w".parse().unwrap());
+        assert_eq!(keys[1].char(), Some(' '));
+        assert_eq!(keys[2].char(), Some('<'));
+
+        assert!(matches!(
+            parse_script("j <nope>"),
+            Err(ScriptError::InvalidKey { .. })
+        ));
+        assert!(matches!(
+            parse_script("j <enter"),
+            Err(ScriptError::Unclosed(_))
+        ));
+    }
+
+    #[test]
+    fn test_script_navigation() {
+        let root = tree("navigation");
+        let outcome = run(&root, None, "2j l <enter>");
+        assert_eq!(
+            outcome.selection.as_deref(),
+            Some(root.join("gamma/src").to_str().unwrap())
+        );
+        assert!(outcome.screen.contains("src"));
+        assert!(!outcome.screen.contains("alpha"));
+
+        // Script ran out before a selection
+        let outcome = run(&root, None, "j");
+        assert_eq!(outcome.selection, None);
+        let lines: Vec<&str> = outcome.screen.lines().collect();
+        assert_eq!(
+            lines[2],
+            " [hidden:off]  [files:off]  [ignored:off]  [deep:off]  [sort:name]"
+        );
+        assert!(lines[4].starts_with(" │ Search:"));
+        assert!(outcome.screen.contains("  0 > beta/"));
+
+        let outcome = run(&root, None, "<esc>");
+        assert_eq!(outcome.selection, None);
+    }
+
+    #[test]
+    fn test_script_columns() {
+        let root = tree("columns");
+        let outcome = run(&root, None, "c n c m");
+        assert!(outcome.screen.contains("  0     0  now > alpha/"));
+        assert!(outcome.screen.contains("  2     1  now   gamma/"));
+
+        // Toggling again hides the column
+        let outcome = run(&root, None, "c n c m c n");
+        assert!(outcome.screen.contains("  0  now > alpha/"));
+    }
+
+    #[test]
+    fn test_script_preview() {
+        let root = tree("preview");
+        std::fs::write(root.join("notes.txt"), "first line\nsecond line\n").unwrap();
+        let outcome = run(&root, None, "2j p");
+        assert!(outcome.screen.contains("┌ gamma ─"), "{}", outcome.screen);
+        assert!(outcome.screen.contains("│1 item · modified just now"));
+        assert!(outcome.screen.contains("│src/"));
+
+        let outcome = run(&root, None, "f G p");
+        assert!(
+            outcome.screen.contains("┌ notes.txt ─"),
+            "{}",
+            outcome.screen
+        );
+        assert!(outcome.screen.contains("│second line"));
+
+        // Toggling again gives the list the whole width
+        let outcome = run(&root, None, "p p");
+        assert!(!outcome.screen.contains("modified"));
+    }
+
+    #[test]
+    fn test_spinner_while_scanning() {
+        let root = tree("spinner");
+        let app = load_app(&root, &Settings::default(), None, None);
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal
+            .draw(|f| ui::draw_fuzzy(f, &app.state, &app.mode))
+            .unwrap();
+        let screen = screen_text(terminal.backend().buffer());
+        assert!(screen.contains("all directories (0) "), "{}", screen);
+        assert!(screen.contains(" scanning 0 "), "{}", screen);
+    }
+
+    #[test]
+    fn test_script_search() {
+        let root = tree("search");
+        let outcome = run(&root, None, "/bet");
+        assert!(outcome.screen.contains("bet"));
+        assert!(!outcome.screen.contains("alpha"));
+
+        let outcome = run(&root, None, "/bet <enter>");
+        assert_eq!(
+            outcome.selection.as_deref(),
+            Some(root.join("beta").to_str().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_script_deep_search() {
+        let root = tree("deep_search");
+        let outcome = run(&root, None, "r /gasrc <enter>");
+        assert_eq!(
+            outcome.selection.as_deref(),
+            Some(root.join("gamma/src").to_str().unwrap())
+        );
+
+        let outcome = run(&root, None, "r");
+        assert!(outcome.screen.contains("[deep:ON depth 8]"));
+        assert!(outcome.screen.contains("deep search (4)"));
+        assert!(outcome.screen.contains("gamma/src/"));
+    }
+
+    #[test]
+    fn test_load_app_with_query_returns_selection() {
+        let root = tree("pick");
+        let db = Database::open_in_memory().unwrap();
+        let settings = Settings::default();
+        let mut app = load_app(&root, &settings, Some(&db), Some("gam"));
+        let keys = parse_script("<enter>").unwrap();
+        let outcome = run_script(
+            &mut app,
+            &mut settings.keymap(),
+            Some(&db),
+            &keys,
+            SCRIPT_SIZE,
+        )
+        .unwrap();
+
+        let gamma = root.join("gamma");
+        assert_eq!(outcome.selection.as_deref(), Some(gamma.to_str().unwrap()));
+        // Picking a directory counts as a visit
+        let frecent = db.get_frecent(FRECENT_LIMIT).unwrap();
+        assert_eq!(frecent.len(), 1);
+        assert_eq!(frecent[0].path, gamma.to_str().unwrap());
+    }
+
+    #[test]
+    fn test_script_ignored_toggle_and_exclude() {
+        let root = tree("ignored");
+        std::fs::write(root.join(".gitignore"), "beta/\n").unwrap();
+        let settings = Settings {
+            exclude: vec!["gamma".to_string()],
+            ..Settings::default()
+        };
+        let script = |keys: &str| {
+            let mut app = load_app(&root, &settings, None, None);
+            let keys = parse_script(keys).unwrap();
+            run_script(&mut app, &mut settings.keymap(), None, &keys, SCRIPT_SIZE)
+                .unwrap()
+                .screen
+        };
+
+        let screen = script("j k");
+        assert!(screen.contains("alpha/"), "{}", screen);
+        assert!(!screen.contains("beta/"));
+        assert!(!screen.contains("gamma/"));
+
+        // Ignored entries come back, excluded names never do
+        let screen = script("i");
+        assert!(screen.contains("[ignored:ON]"));
+        assert!(screen.contains("beta/"), "{}", screen);
+        assert!(!screen.contains("gamma/"));
+    }
+
+    #[test]
+    fn test_script_create_error_in_status_line() {
+        let root = tree("create_error");
+        let outcome = run(&root, None, "a alpha <enter>");
+        let lines: Vec<&str> = outcome.screen.lines().collect();
+        assert!(
+            lines[2].starts_with(" create failed: "),
+            "{}",
+            outcome.screen
+        );
+
+        // The next key brings the toggles back
+        let outcome = run(&root, None, "a alpha <enter> j");
+        assert!(outcome.screen.contains("[hidden:off]"));
+    }
+
+    #[test]
+    fn test_failed_bookmark_write_in_status_line() {
+        let root = tree("bookmark_error");
+        let db = Database::open_in_memory().unwrap();
+        let mut app = load_app(&root, &Settings::default(), Some(&db), None);
+        let effect = Effect::OverwriteBookmark {
+            path: "alpha".to_string(),
+            name: "alpha".to_string(),
+            key: "a".to_string(),
+        };
+        assert!(matches!(
+            perform(&mut app, Some(&db), vec![effect]),
+            Flow::Continue
+        ));
+        let status = app.state.status.as_deref().unwrap_or_default();
+        assert!(status.starts_with("bookmark failed: "), "{}", status);
+        assert!(db.get_by_bookmark_key("a").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_sort_is_remembered_between_runs() {
+        let root = tree("remember_sort");
+        let db = Database::open_in_memory().unwrap();
+        run(&root, Some(&db), "s S <esc>");
+
+        let outcome = run(&root, Some(&db), "");
+        assert!(outcome.screen.contains("[sort:modified rev]"));
+
+        // A run that ends without exiting keeps the saved sort
+        run(&root, Some(&db), "s");
+        run(&root, Some(&db), "S <enter>");
+        let outcome = run(&root, Some(&db), "");
+        assert!(
+            outcome.screen.contains("[sort:modified]"),
+            "{}",
+            outcome.screen
+        );
+
+        // Without a database the configured sort applies
+        let outcome = run(&root, None, "");
+        assert!(outcome.screen.contains("[sort:name]"));
+
+        // So does a sort set in config.toml or JUMP_SORT
+        let mut settings = Settings::default();
+        settings
+            .apply_env(|name| (name == "JUMP_SORT").then(|| "size".to_string()))
+            .unwrap();
+        let mut app = load_app(&root, &settings, Some(&db), None);
+        let outcome = run_script(
+            &mut app,
+            &mut settings.keymap(),
+            Some(&db),
+            &[],
+            SCRIPT_SIZE,
+        )
+        .unwrap();
+        assert!(outcome.screen.contains("[sort:size]"), "{}", outcome.screen);
+    }
+
+    #[test]
+    fn test_script_forget_and_repoint_missing_entries() {
+        let root = tree("missing");
+        let (gone, old) = (root.join("gone"), root.join("old"));
+        let db = Database::open_in_memory().unwrap();
+        db.set_bookmark(gone.to_str().unwrap(), "gone", "g")
+            .unwrap();
+        db.record_visit(old.to_str().unwrap()).unwrap();
+        let settings = Settings::default();
+        let script = |query: &str, keys: &str| {
+            let mut app = load_app(&root, &settings, Some(&db), Some(query));
+            app.state.set_stale_paths(vec![
+                gone.to_string_lossy().into_owned(),
+                old.to_string_lossy().into_owned(),
+            ]);
+            let keys = parse_script(keys).unwrap();
+            run_script(
+                &mut app,
+                &mut settings.keymap(),
+                Some(&db),
+                &keys,
+                SCRIPT_SIZE,
+            )
+            .unwrap()
+        };
+
+        let outcome = script("gone", "R <ctrl-u> beta <enter>");
+        assert_eq!(outcome.selection, None);
+        let bookmark = db.get_by_bookmark_key("g").unwrap().unwrap();
+        assert_eq!(bookmark.path, root.join("beta").to_str().unwrap());
+
+        script("old", "x <enter>");
+        let paths: Vec<String> = db
+            .get_all_entries()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.path)
+            .collect();
+        assert_eq!(paths, [root.join("beta").to_str().unwrap()]);
+
+        // Repointing to a directory that doesn't exist is refused
+        db.set_bookmark(gone.to_str().unwrap(), "gone", "h")
+            .unwrap();
+        let outcome = script("gone", "R <enter>");
+        assert!(
+            outcome.screen.contains("repoint failed"),
+            "{}",
+            outcome.screen
+        );
+        assert_eq!(
+            db.get_by_bookmark_key("h").unwrap().unwrap().path,
+            gone.to_str().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_script_bookmark() {
+        let root = tree("bookmark");
+        let db = Database::open_in_memory().unwrap();
+        let outcome = run(&root, Some(&db), "j bw <enter>");
+        assert_eq!(outcome.selection, None);
+
+        let bookmark = db.get_by_bookmark_key("w").unwrap().unwrap();
+        assert_eq!(bookmark.path, root.join("beta").to_str().unwrap());
+
+        // Reusing the alias asks before overwriting it
+        let outcome = run(&root, Some(&db), "bw <enter>");
+        assert!(outcome.screen.contains("already"));
+        run(&root, Some(&db), "bw <enter> <enter>");
+        let bookmark = db.get_by_bookmark_key("w").unwrap().unwrap();
+        assert_eq!(bookmark.path, root.join("alpha").to_str().unwrap());
+    }
+}
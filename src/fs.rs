@@ -1,11 +1,130 @@
+//! Directory scanning and path helpers
+
 use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 use walkdir::WalkDir;
 
+/// Entries per batch sent by `spawn_walk`
+const WALK_BATCH: usize = 256;
+/// Send a partial batch after this long so slow filesystems still show progress
+const WALK_FLUSH: Duration = Duration::from_millis(50);
+/// Per-directory ignore file read alongside `.gitignore` and `.ignore`
+pub const IGNORE_FILE: &str = ".jumpignore";
+
+/// A child of a scanned directory
 #[derive(Debug, Clone)]
 pub struct DirEntry {
     pub path: PathBuf,
     pub name: String,
+    /// Directory, or a symlink that resolves to one
     pub is_dir: bool,
+    pub link: Option<Symlink>,
+    /// Filled by `metadata` on first use and shared between clones
+    pub meta: Arc<OnceLock<Option<EntryMeta>>>,
+}
+
+impl DirEntry {
+    /// Size, times and ownership, read from disk the first time they are asked for
+    pub fn metadata(&self) -> Option<&EntryMeta> {
+        self.meta
+            .get_or_init(|| EntryMeta::read(&self.path, self.is_dir))
+            .as_ref()
+    }
+}
+
+/// Metadata shown in the optional list columns
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntryMeta {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    /// `st_mode` with file type and permission bits; `None` off Unix
+    pub mode: Option<u32>,
+    pub owner: Option<String>,
+    /// Children of a directory; `None` for files and unreadable directories
+    pub item_count: Option<usize>,
+}
+
+impl EntryMeta {
+    /// Metadata of `path`, following symlinks unless they dangle
+    pub fn read(path: &Path, is_dir: bool) -> Option<Self> {
+        let metadata = std::fs::metadata(path)
+            .or_else(|_| std::fs::symlink_metadata(path))
+            .ok()?;
+        #[cfg(unix)]
+        let (mode, owner) = {
+            use std::os::unix::fs::MetadataExt;
+            (Some(metadata.mode()), Some(user_name(metadata.uid())))
+        };
+        #[cfg(not(unix))]
+        let (mode, owner) = (None, None);
+        Some(Self {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            mode,
+            owner,
+            item_count: is_dir
+                .then(|| std::fs::read_dir(path).ok().map(Iterator::count))
+                .flatten(),
+        })
+    }
+}
+
+/// Login name for `uid` from /etc/passwd, or the number if it isn't listed
+#[cfg(unix)]
+fn user_name(uid: u32) -> String {
+    static USERS: OnceLock<std::collections::HashMap<u32, String>> = OnceLock::new();
+    let users = USERS.get_or_init(|| {
+        std::fs::read_to_string("/etc/passwd")
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split(':');
+                let name = fields.next()?;
+                let uid = fields.nth(1)?.parse().ok()?;
+                Some((uid, name.to_string()))
+            })
+            .collect()
+    });
+    users.get(&uid).cloned().unwrap_or_else(|| uid.to_string())
+}
+
+/// Where a listed symlink points
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symlink {
+    /// The link's contents as written
+    pub target: PathBuf,
+    /// Canonical path of the final target; `None` if it is dangling or a cycle of links
+    pub resolved: Option<PathBuf>,
+    /// Resolves to the directory holding the link or one of its ancestors
+    pub is_loop: bool,
+}
+
+impl Symlink {
+    /// Read and resolve the symlink at `path`
+    pub fn read(path: &Path) -> Self {
+        let target = std::fs::read_link(path).unwrap_or_default();
+        let resolved = std::fs::canonicalize(path).ok();
+        let parent = path.parent().and_then(|p| std::fs::canonicalize(p).ok());
+        let is_loop = match (&resolved, parent) {
+            (Some(resolved), Some(parent)) => parent.starts_with(resolved),
+            _ => false,
+        };
+        Self {
+            target,
+            resolved,
+            is_loop,
+        }
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.resolved.as_ref().is_some_and(|p| p.is_dir())
+    }
+
+    pub fn is_broken(&self) -> bool {
+        self.resolved.is_none()
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -18,6 +137,7 @@ pub enum ScanError {
     IoError(#[from] std::io::Error),
 }
 
+/// List the direct children of `dir`, directories first, then by name
 pub fn scan_directories(dir: &Path, show_hidden: bool, show_files: bool) -> Result<Vec<DirEntry>, ScanError> {
     if !dir.exists() {
         return Err(ScanError::NotFound(dir.to_path_buf()));
@@ -37,11 +157,14 @@ pub fn scan_directories(dir: &Path, show_hidden: bool, show_files: bool) -> Resu
     {
         match entry {
             Ok(e) => {
-                let is_dir = e.file_type().is_dir();
+                let link = e.path_is_symlink().then(|| Symlink::read(e.path()));
+                let is_dir = link
+                    .as_ref()
+                    .map_or(e.file_type().is_dir(), Symlink::is_dir);
                 if !is_dir && !show_files {
                     continue;
                 }
-                if !is_dir && !e.file_type().is_file() {
+                if !is_dir && link.is_none() && !e.file_type().is_file() {
                     continue;
                 }
 
@@ -57,6 +180,8 @@ pub fn scan_directories(dir: &Path, show_hidden: bool, show_files: bool) -> Resu
                     name: e.file_name().to_string_lossy().into_owned(),
                     path: e.path().to_path_buf(),
                     is_dir,
+                    link,
+                    meta: Default::default(),
                 });
             }
             Err(e) => {
@@ -76,6 +201,174 @@ pub fn scan_directories(dir: &Path, show_hidden: bool, show_files: bool) -> Resu
     Ok(entries)
 }
 
+/// What `walk_tree` descends into and reports
+#[derive(Debug, Clone)]
+pub struct WalkOptions {
+    /// Levels below the root; 1 lists only its children
+    pub max_depth: usize,
+    pub show_hidden: bool,
+    pub show_files: bool,
+    /// List entries matched by `.gitignore`, `.ignore`, global git excludes and `.jumpignore`
+    pub show_ignored: bool,
+    /// Entry names always skipped along with everything under them
+    pub exclude: Vec<String>,
+    /// Read each entry's metadata before sending it, so sorting by it doesn't block
+    pub read_metadata: bool,
+}
+
+/// Walk the subtree under `root`, handing entries to `emit` in batches until it returns false
+///
+/// Each entry's `name` is its path relative to `root`, so matching sees the whole path.
+/// Batches are cut at `WALK_BATCH` entries or every `WALK_FLUSH`, whichever comes first.
+/// Ignore files in `root`'s parents apply too, so a subdirectory of a repository is
+/// filtered by the repository's `.gitignore`; `.gitignore` is honoured outside git
+/// repositories as well. Symlinks are listed with the type they
+/// resolve to but never descended into, so links back up the tree cannot loop.
+pub fn walk_tree(root: &Path, options: &WalkOptions, mut emit: impl FnMut(Vec<DirEntry>) -> bool) {
+    let exclude = options.exclude.clone();
+    let mut builder = ignore::WalkBuilder::new(root);
+    builder
+        .max_depth(Some(options.max_depth))
+        .follow_links(false)
+        .standard_filters(!options.show_ignored)
+        .require_git(false)
+        .hidden(!options.show_hidden)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(move |e| {
+            let name = e.file_name().to_string_lossy();
+            !exclude.iter().any(|excluded| *excluded == name)
+        });
+    if !options.show_ignored {
+        builder.add_custom_ignore_filename(IGNORE_FILE);
+    }
+
+    let mut batch = Vec::with_capacity(WALK_BATCH);
+    let mut last_emit = Instant::now();
+    // The root itself has to go through the walker for its ignore files to be read,
+    // so it is skipped here rather than with `min_depth`
+    for entry in builder.build().filter_map(Result::ok) {
+        let Some(file_type) = entry.file_type().filter(|_| entry.depth() > 0) else {
+            continue;
+        };
+        let link = file_type.is_symlink().then(|| Symlink::read(entry.path()));
+        let is_dir = link.as_ref().map_or(file_type.is_dir(), Symlink::is_dir);
+        let listed = is_dir || (options.show_files && (file_type.is_file() || link.is_some()));
+        if !listed {
+            continue;
+        }
+        let path = entry.into_path();
+        let entry = DirEntry {
+            name: path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .into_owned(),
+            path,
+            is_dir,
+            link,
+            meta: Default::default(),
+        };
+        if options.read_metadata {
+            entry.metadata();
+        }
+        batch.push(entry);
+        if batch.len() == WALK_BATCH || last_emit.elapsed() >= WALK_FLUSH {
+            if !emit(std::mem::take(&mut batch)) {
+                return;
+            }
+            last_emit = Instant::now();
+        }
+    }
+    if !batch.is_empty() {
+        emit(batch);
+    }
+}
+
+/// Run `walk_tree` on a background thread; dropping the receiver stops the walk
+pub fn spawn_walk(root: &Path, options: WalkOptions) -> mpsc::Receiver<Vec<DirEntry>> {
+    let (tx, rx) = mpsc::channel();
+    let root = root.to_path_buf();
+    thread::spawn(move || walk_tree(&root, &options, |batch| tx.send(batch).is_ok()));
+    rx
+}
+
+/// Reports changes to the entries of a directory, or of its subtree (inotify on Linux)
+#[derive(Debug)]
+pub struct DirWatcher {
+    watcher: notify::RecommendedWatcher,
+    events: mpsc::Receiver<()>,
+    root: PathBuf,
+    depth: usize,
+}
+
+impl DirWatcher {
+    /// Watch the entries of `dir` down to `depth` levels, as listed by `walk_tree`;
+    /// `None` if the platform refuses. Only `dir` itself is watched until the
+    /// subdirectories the walk lists are passed to `watch_entries`.
+    pub fn new(dir: &Path, depth: usize) -> Option<Self> {
+        use notify::{RecursiveMode, Watcher};
+
+        let (tx, rx) = mpsc::channel();
+        let root = dir.to_path_buf();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            if changes_listing(event, &root, depth) {
+                let _ = tx.send(());
+            }
+        })
+        .ok()?;
+        watcher.watch(dir, RecursiveMode::NonRecursive).ok()?;
+        Some(Self {
+            watcher,
+            events: rx,
+            root: dir.to_path_buf(),
+            depth,
+        })
+    }
+
+    /// Also watch the listed directories whose entries are within `depth`, each on its
+    /// own, so ignored and excluded subtrees are never visited. Directories that can't
+    /// be watched, e.g. once the inotify watch limit is reached, are left out.
+    pub fn watch_entries(&mut self, entries: &[DirEntry]) {
+        use notify::{RecursiveMode, Watcher};
+
+        for entry in entries.iter().filter(|e| e.is_dir && e.link.is_none()) {
+            let within = entry
+                .path
+                .strip_prefix(&self.root)
+                .is_ok_and(|rel| rel.components().count() < self.depth);
+            if within {
+                let _ = self.watcher.watch(&entry.path, RecursiveMode::NonRecursive);
+            }
+        }
+    }
+
+    /// Levels below the watched directory that are reported
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Whether entries were added, removed or renamed since the last call
+    pub fn changed(&self) -> bool {
+        self.events.try_iter().count() > 0
+    }
+}
+
+/// Entries within `depth` levels of `root` were created, removed or renamed; opening and
+/// reading directories (our own scans) does not count
+fn changes_listing(event: notify::Result<notify::Event>, root: &Path, depth: usize) -> bool {
+    use notify::event::{EventKind, ModifyKind};
+
+    event.is_ok_and(|e| {
+        matches!(
+            e.kind,
+            EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(_))
+        ) && e.paths.iter().any(|path| {
+            path.strip_prefix(root)
+                .is_ok_and(|rel| rel.components().count() <= depth)
+        })
+    })
+}
+
 /// Safe way to get parent directory with fallbacks
 pub fn get_safe_parent(dir: &Path) -> Option<PathBuf> {
     if dir == Path::new("/") {
@@ -86,6 +379,85 @@ pub fn get_safe_parent(dir: &Path) -> Option<PathBuf> {
         .filter(|pb| !pb.as_os_str().is_empty())
 }
 
+/// Expand `~`, `~/...`, `$VAR` and `${VAR}` using the process environment.
+/// Unknown variables are left as written.
+pub fn expand_path(path: &str) -> String {
+    let home = std::env::var("HOME").ok();
+    let path = match (path, home.as_deref()) {
+        ("~", Some(home)) => home.to_string(),
+        (p, Some(home)) if p.starts_with("~/") => format!("{}{}", home, &p[1..]),
+        (p, _) => p.to_string(),
+    };
+
+    let mut expanded = String::with_capacity(path.len());
+    let mut rest = path.as_str();
+    while let Some(pos) = rest.find('$') {
+        expanded.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        let (name, consumed) = if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            }
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], end)
+        };
+
+        match std::env::var(name) {
+            Ok(value) if !name.is_empty() => expanded.push_str(&value),
+            _ => expanded.push_str(&rest[pos..pos + 1 + consumed]),
+        }
+        rest = &after[consumed..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Remove `.` and `..` components without touching the filesystem
+pub fn clean_path(path: &Path) -> PathBuf {
+    use std::path::Component;
+
+    let mut cleaned = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match cleaned.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    cleaned.pop();
+                }
+                // `..` at the root stays at the root
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {}
+                _ => cleaned.push(component),
+            },
+            other => cleaned.push(other),
+        }
+    }
+    cleaned
+}
+
+/// Normalize a user-supplied path: expand `~` and environment variables,
+/// make it absolute against `base`, and drop `.`/`..` components. With
+/// `resolve_symlinks`, existing paths are also canonicalized.
+pub fn normalize_path(path: &str, base: &Path, resolve_symlinks: bool) -> PathBuf {
+    let expanded = PathBuf::from(expand_path(path));
+    let absolute = if expanded.is_absolute() {
+        expanded
+    } else {
+        base.join(expanded)
+    };
+    let cleaned = clean_path(&absolute);
+
+    if resolve_symlinks {
+        if let Ok(resolved) = std::fs::canonicalize(&cleaned) {
+            return resolved;
+        }
+    }
+    cleaned
+}
+
 /// Check if a path is accessible (exists and readable)
 pub fn is_accessible(dir: &Path) -> bool {
     dir.exists() && dir.is_dir()
@@ -127,6 +499,171 @@ mod tests {
         assert!(matches!(result.unwrap_err(), ScanError::NotDirectory(_)));
     }
 
+    #[test]
+    fn test_walk_tree() {
+        let root = env::temp_dir().join("jump_test_walk");
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["src/ui", "src/.cache", "target/debug", "docs"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join("src/ui/fuzzy.rs"), "").unwrap();
+
+        let mut options = WalkOptions {
+            max_depth: 8,
+            show_hidden: false,
+            show_files: false,
+            show_ignored: false,
+            exclude: vec!["target".to_string()],
+            read_metadata: false,
+        };
+        let walk = |options: &WalkOptions| {
+            let mut names = Vec::new();
+            walk_tree(&root, options, |batch| {
+                names.extend(batch.into_iter().map(|e| e.name));
+                true
+            });
+            names
+        };
+        assert_eq!(walk(&options), ["docs", "src", "src/ui"]);
+
+        options.show_files = true;
+        options.show_hidden = true;
+        options.max_depth = 2;
+        assert_eq!(walk(&options), ["docs", "src", "src/.cache", "src/ui"]);
+
+        options.max_depth = 3;
+        options.read_metadata = true;
+        let rx = spawn_walk(&root, options);
+        let entries: Vec<DirEntry> = rx.iter().flatten().collect();
+        assert!(entries.iter().any(|e| e.name == "src/ui/fuzzy.rs"));
+        // Metadata arrives already read
+        assert!(entries.iter().all(|e| e.meta.get().is_some()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_tree_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let root = env::temp_dir().join("jump_test_walk_symlinks");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("real/inner")).unwrap();
+        std::fs::write(root.join("notes.txt"), "").unwrap();
+        symlink("real", root.join("linked")).unwrap();
+        symlink("notes.txt", root.join("notes_link")).unwrap();
+        symlink("..", root.join("up")).unwrap();
+        symlink("missing", root.join("dangling")).unwrap();
+
+        let mut options = WalkOptions {
+            max_depth: 3,
+            show_hidden: false,
+            show_files: false,
+            show_ignored: false,
+            exclude: Vec::new(),
+            read_metadata: false,
+        };
+        let walk = |options: &WalkOptions| {
+            let mut entries = Vec::new();
+            walk_tree(&root, options, |batch| {
+                entries.extend(batch);
+                true
+            });
+            entries
+        };
+
+        // Linked directories are listed but not descended into
+        let entries = walk(&options);
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["linked", "real", "real/inner", "up"]);
+        let linked = entries[0].link.as_ref().unwrap();
+        assert!(entries[0].is_dir);
+        assert_eq!(linked.target, PathBuf::from("real"));
+        assert!(!linked.is_loop);
+        assert!(entries[3].link.as_ref().unwrap().is_loop);
+        assert!(entries[1].link.is_none());
+
+        options.max_depth = 1;
+        options.show_files = true;
+        let entries = walk(&options);
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "dangling",
+                "linked",
+                "notes.txt",
+                "notes_link",
+                "real",
+                "up"
+            ]
+        );
+        assert!(entries[0].link.as_ref().unwrap().is_broken());
+        assert!(!entries[3].is_dir);
+
+        let scanned = scan_directories(&root, false, false).unwrap();
+        let names: Vec<&str> = scanned.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["linked", "real", "up"]);
+    }
+
+    #[test]
+    fn test_walk_tree_ignore_files() {
+        let root = env::temp_dir().join("jump_test_walk_ignore");
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in [".git", "build", "tmp", "src/out", "node_modules"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join(".gitignore"), "build/\n").unwrap();
+        std::fs::write(root.join("src/.ignore"), "out\n").unwrap();
+        std::fs::write(root.join(IGNORE_FILE), "tmp\n").unwrap();
+
+        let mut options = WalkOptions {
+            max_depth: 2,
+            show_hidden: true,
+            show_files: false,
+            show_ignored: false,
+            exclude: vec![".git".to_string(), "node_modules".to_string()],
+            read_metadata: false,
+        };
+        let walk = |options: &WalkOptions| {
+            let mut names = Vec::new();
+            walk_tree(&root, options, |batch| {
+                names.extend(batch.into_iter().map(|e| e.name));
+                true
+            });
+            names
+        };
+        assert_eq!(walk(&options), ["src"]);
+
+        // Excluded names stay hidden even when ignore files are not applied
+        options.show_ignored = true;
+        assert_eq!(walk(&options), ["build", "src", "src/out", "tmp"]);
+    }
+
+    #[test]
+    fn test_walk_tree_gitignore_outside_repository() {
+        let root = env::temp_dir().join("jump_test_walk_no_git");
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["build", "src"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join(".gitignore"), "build/\n").unwrap();
+
+        let options = WalkOptions {
+            max_depth: 1,
+            show_hidden: false,
+            show_files: false,
+            show_ignored: false,
+            exclude: Vec::new(),
+            read_metadata: false,
+        };
+        let mut names = Vec::new();
+        walk_tree(&root, &options, |batch| {
+            names.extend(batch.into_iter().map(|e| e.name));
+            true
+        });
+        assert_eq!(names, ["src"]);
+    }
+
     #[test]
     fn test_get_safe_parent() {
         let path = Path::new("/home/user/projects");
@@ -139,6 +676,49 @@ mod tests {
         assert_eq!(get_safe_parent(single), None);
     }
 
+    #[test]
+    fn test_expand_path() {
+        let home = env::var("HOME").unwrap();
+        assert_eq!(expand_path("~"), home);
+        assert_eq!(expand_path("~/src"), format!("{}/src", home));
+        assert_eq!(expand_path("$HOME/src"), format!("{}/src", home));
+        assert_eq!(expand_path("${HOME}/src"), format!("{}/src", home));
+        assert_eq!(expand_path("/a/$JUMP_UNSET_VAR/b"), "/a/$JUMP_UNSET_VAR/b");
+        assert_eq!(expand_path("/cost$"), "/cost$");
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        let base = Path::new("/home/user");
+        assert_eq!(
+            normalize_path("projects/../docs/./notes/", base, false),
+            PathBuf::from("/home/user/docs/notes")
+        );
+        assert_eq!(normalize_path("/srv//app/..", base, false), PathBuf::from("/srv"));
+        assert_eq!(normalize_path("/..", base, false), PathBuf::from("/"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_normalize_path_resolves_symlinks() {
+        let dir = env::temp_dir().join("jump_test_normalize");
+        let target = dir.join("target");
+        let link = dir.join("link");
+        std::fs::create_dir_all(&target).unwrap();
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let resolved = normalize_path(link.to_str().unwrap(), Path::new("/"), true);
+        let preserved = normalize_path(link.to_str().unwrap(), Path::new("/"), false);
+        std::fs::remove_dir_all(&dir).ok();
+
+        let expected = std::fs::canonicalize(env::temp_dir())
+            .unwrap()
+            .join("jump_test_normalize/target");
+        assert_eq!(resolved, expected);
+        assert_eq!(preserved, link);
+    }
+
     #[test]
     fn test_is_accessible() {
         assert!(is_accessible(Path::new("/tmp")));
@@ -1,3 +1,7 @@
+//! Fuzzy matching
+
 pub mod matcher;
+pub mod query;
 
 pub use matcher::FuzzyMatchEngine;
+pub use query::Query;
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 
+/// Skim-style fuzzy matcher; cheap to clone
 #[derive(Clone)]
 pub struct FuzzyMatchEngine {
     matcher: Arc<SkimMatcherV2>,
@@ -14,6 +15,12 @@ impl std::fmt::Debug for FuzzyMatchEngine {
     }
 }
 
+impl Default for FuzzyMatchEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FuzzyMatchEngine {
     pub fn new() -> Self {
         Self {
@@ -21,9 +28,15 @@ impl FuzzyMatchEngine {
         }
     }
 
+    /// Score `text` against `pattern`, or `None` if it does not match
     pub fn get_score(&self, pattern: &str, text: &str) -> Option<i64> {
         (*self.matcher).fuzzy_match(text, pattern)
     }
+
+    /// Like `get_score`, also returning the char positions in `text` that matched
+    pub fn get_score_indices(&self, pattern: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+        (*self.matcher).fuzzy_indices(text, pattern)
+    }
 }
 
 #[cfg(test)]
@@ -38,4 +51,13 @@ mod tests {
         assert!(score.is_some());
         assert!(score.unwrap() > 0);
     }
+
+    #[test]
+    fn test_indices_are_char_positions() {
+        let matcher = FuzzyMatchEngine::new();
+        let (score, indices) = matcher.get_score_indices("dc", "déjà-code").unwrap();
+        assert_eq!(Some(score), matcher.get_score("dc", "déjà-code"));
+        assert_eq!(indices, [0, 5]);
+        assert_eq!(matcher.get_score_indices("zz", "abc"), None);
+    }
 }
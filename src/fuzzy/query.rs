@@ -0,0 +1,262 @@
+//! fzf-style extended search syntax
+//!
+//! Space-separated terms must all match. Each term is fuzzy unless marked:
+//!
+//! | Term    | Matches                                            |
+//! |---------|----------------------------------------------------|
+//! | `src`   | fuzzy: `s`, `r`, `c` in order                      |
+//! | `'src`  | `src` anywhere                                     |
+//! | `^src`  | names starting with `src`                          |
+//! | `.rs$`  | names ending with `.rs`                            |
+//! | `^src$` | exactly `src`                                      |
+//! | `!test` | names without `test`; `!^x` and `!x$` anchor it    |
+//!
+//! A `|` between terms makes them alternatives: `^core go$ | rs$` matches names that
+//! start with `core` and end with `go` or `rs`. Like the fuzzy matcher, terms are
+//! case-insensitive unless they contain an uppercase letter.
+
+use super::FuzzyMatchEngine;
+
+/// How a term compares against the text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TermKind {
+    Fuzzy,
+    /// Substring anywhere, `'term`
+    Exact,
+    /// `^term`
+    Prefix,
+    /// `term$`
+    Suffix,
+    /// `^term$`
+    Equal,
+}
+
+/// One search term with its markers stripped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    pub kind: TermKind,
+    pub text: String,
+    /// `!term`: the text must not match; negated terms are never fuzzy
+    pub negate: bool,
+}
+
+impl Term {
+    fn parse(token: &str) -> Self {
+        let (negate, rest) = match token.strip_prefix('!') {
+            Some(rest) if !rest.is_empty() => (true, rest),
+            _ => (false, token),
+        };
+        let (exact, rest) = match rest.strip_prefix('\'') {
+            Some(rest) if !rest.is_empty() => (true, rest),
+            _ => (false, rest),
+        };
+        let (prefix, rest) = match rest.strip_prefix('^') {
+            Some(rest) if !exact && !rest.is_empty() => (true, rest),
+            _ => (false, rest),
+        };
+        let (suffix, rest) = match rest.strip_suffix('$') {
+            Some(rest) if !exact && !rest.is_empty() => (true, rest),
+            _ => (false, rest),
+        };
+        let kind = match (prefix, suffix) {
+            (true, true) => TermKind::Equal,
+            (true, false) => TermKind::Prefix,
+            (false, true) => TermKind::Suffix,
+            _ if exact || negate => TermKind::Exact,
+            _ => TermKind::Fuzzy,
+        };
+        Self {
+            kind,
+            text: rest.to_string(),
+            negate,
+        }
+    }
+
+    /// Score and matched char positions, or `None` if `text` does not satisfy the term
+    fn matches(&self, matcher: &FuzzyMatchEngine, text: &str) -> Option<(i64, Vec<usize>)> {
+        let found = if self.kind == TermKind::Fuzzy {
+            matcher.get_score_indices(&self.text, text)
+        } else {
+            self.find(text).map(|start| {
+                // Rank exact hits like the fuzzy matcher would, so the two kinds mix
+                let score = matcher.get_score(&self.text, text).unwrap_or(0);
+                let len = self.text.chars().count();
+                (score, (start..start + len).collect())
+            })
+        };
+        match (found, self.negate) {
+            (Some(_), true) => None,
+            (None, true) => Some((0, Vec::new())),
+            (found, false) => found,
+        }
+    }
+
+    /// Char position where the term's text occurs in `text` as its kind requires
+    fn find(&self, text: &str) -> Option<usize> {
+        let case_sensitive = self.text.chars().any(char::is_uppercase);
+        let fold = |c: char| -> char {
+            if case_sensitive {
+                c
+            } else {
+                c.to_lowercase().next().unwrap_or(c)
+            }
+        };
+        let haystack: Vec<char> = text.chars().map(fold).collect();
+        let needle: Vec<char> = self.text.chars().map(fold).collect();
+        if needle.len() > haystack.len() {
+            return None;
+        }
+        let at = |start: usize| haystack[start..].starts_with(&needle);
+        let last = haystack.len() - needle.len();
+        match self.kind {
+            TermKind::Prefix => at(0).then_some(0),
+            TermKind::Suffix => at(last).then_some(last),
+            TermKind::Equal => (last == 0 && at(0)).then_some(0),
+            TermKind::Exact | TermKind::Fuzzy => (0..=last).find(|&start| at(start)),
+        }
+    }
+}
+
+/// A parsed search: every group must match, through any one of its terms
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    pub groups: Vec<Vec<Term>>,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Self {
+        let mut groups: Vec<Vec<Term>> = Vec::new();
+        let mut join = false;
+        for token in input.split_whitespace() {
+            if token == "|" {
+                join = !groups.is_empty();
+                continue;
+            }
+            let term = Term::parse(token);
+            match groups.last_mut() {
+                Some(group) if join => group.push(term),
+                _ => groups.push(vec![term]),
+            }
+            join = false;
+        }
+        Self { groups }
+    }
+
+    /// No terms; matches everything
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Total score and sorted matched char positions, or `None` if `text` does not match
+    ///
+    /// Each group contributes its best-scoring alternative.
+    pub fn matches(&self, matcher: &FuzzyMatchEngine, text: &str) -> Option<(i64, Vec<usize>)> {
+        let mut total = 0;
+        let mut indices = Vec::new();
+        for group in &self.groups {
+            let (score, matched) = group
+                .iter()
+                .filter_map(|term| term.matches(matcher, text))
+                .max_by_key(|(score, _)| *score)?;
+            total += score;
+            indices.extend(matched);
+        }
+        indices.sort_unstable();
+        indices.dedup();
+        Some((total, indices))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(kind: TermKind, text: &str, negate: bool) -> Term {
+        Term {
+            kind,
+            text: text.to_string(),
+            negate,
+        }
+    }
+
+    fn matching<'a>(query: &str, names: &[&'a str]) -> Vec<&'a str> {
+        let matcher = FuzzyMatchEngine::new();
+        let query = Query::parse(query);
+        names
+            .iter()
+            .copied()
+            .filter(|name| query.matches(&matcher, name).is_some())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let query = Query::parse(" src 'lib ^core go$ | rs$  !^test ^main$ ! ");
+        assert_eq!(
+            query.groups,
+            [
+                vec![term(TermKind::Fuzzy, "src", false)],
+                vec![term(TermKind::Exact, "lib", false)],
+                vec![term(TermKind::Prefix, "core", false)],
+                vec![
+                    term(TermKind::Suffix, "go", false),
+                    term(TermKind::Suffix, "rs", false),
+                ],
+                vec![term(TermKind::Prefix, "test", true)],
+                vec![term(TermKind::Equal, "main", false)],
+                // Markers with nothing after them are plain text
+                vec![term(TermKind::Fuzzy, "!", false)],
+            ]
+        );
+        assert_eq!(
+            Query::parse("a | b | c").groups,
+            [vec![
+                term(TermKind::Fuzzy, "a", false),
+                term(TermKind::Fuzzy, "b", false),
+                term(TermKind::Fuzzy, "c", false),
+            ]]
+        );
+        assert_eq!(Query::parse("| a |").groups.len(), 1);
+        assert!(Query::parse("   ").is_empty());
+    }
+
+    #[test]
+    fn test_term_kinds() {
+        let names = ["src", "resource", "my-src", "Source", "src-old", "tests"];
+        assert_eq!(
+            matching("src", &names),
+            ["src", "resource", "my-src", "Source", "src-old"]
+        );
+        assert_eq!(matching("'src", &names), ["src", "my-src", "src-old"]);
+        assert_eq!(matching("^src", &names), ["src", "src-old"]);
+        assert_eq!(matching("src$", &names), ["src", "my-src"]);
+        assert_eq!(matching("^src$", &names), ["src"]);
+        assert_eq!(matching("!src", &names), ["resource", "Source", "tests"]);
+        assert_eq!(
+            matching("!^src s", &names),
+            ["resource", "my-src", "Source", "tests"]
+        );
+        assert_eq!(matching("^S", &names), ["Source"]);
+        assert_eq!(matching("^s", &names), ["src", "Source", "src-old"]);
+    }
+
+    #[test]
+    fn test_and_or() {
+        let names = ["core.go", "core.rs", "core.py", "lib.rs"];
+        assert_eq!(matching("^core go$ | rs$", &names), ["core.go", "core.rs"]);
+        assert_eq!(matching("rs$ !lib", &names), ["core.rs"]);
+        assert_eq!(matching("", &names), names);
+    }
+
+    #[test]
+    fn test_indices_merge_across_terms() {
+        let matcher = FuzzyMatchEngine::new();
+        let (_, indices) = Query::parse("^co rs$ 're")
+            .matches(&matcher, "core.rs")
+            .unwrap();
+        assert_eq!(indices, [0, 1, 2, 3, 5, 6]);
+
+        let (score, indices) = Query::parse("!py").matches(&matcher, "core.rs").unwrap();
+        assert_eq!((score, indices), (0, Vec::new()));
+    }
+}
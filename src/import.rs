@@ -0,0 +1,192 @@
+//! Import directory history from other jumpers
+//!
+//! Supported formats:
+//! - zoxide: `zoxide query --list --score` output (`<score> <path>`)
+//! - autojump: `autojump.txt` (`<weight>\t<path>`)
+//! - z / fasd: `~/.z` and `~/.fasd` (`<path>|<rank>|<timestamp>`)
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::database::entry::VisitRecord;
+
+/// A tool whose history can be imported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportSource {
+    Zoxide,
+    Autojump,
+    Z,
+    Fasd,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    #[error("Unknown import source '{0}' (expected zoxide, autojump, z or fasd)")]
+    UnknownSource(String),
+    #[error("No default history file for {0} - pass the file explicitly")]
+    NoDefaultFile(ImportSource),
+}
+
+impl FromStr for ImportSource {
+    type Err = ImportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "zoxide" => Ok(ImportSource::Zoxide),
+            "autojump" => Ok(ImportSource::Autojump),
+            "z" => Ok(ImportSource::Z),
+            "fasd" => Ok(ImportSource::Fasd),
+            other => Err(ImportError::UnknownSource(other.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for ImportSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ImportSource::Zoxide => "zoxide",
+            ImportSource::Autojump => "autojump",
+            ImportSource::Z => "z",
+            ImportSource::Fasd => "fasd",
+        };
+        f.write_str(name)
+    }
+}
+
+impl ImportSource {
+    /// Where the tool keeps its history by default. zoxide uses a binary
+    /// database, so its text dump has to be supplied explicitly.
+    pub fn default_file(self) -> Result<PathBuf, ImportError> {
+        let dirs = directories::BaseDirs::new().ok_or(ImportError::NoDefaultFile(self))?;
+        match self {
+            ImportSource::Zoxide => Err(ImportError::NoDefaultFile(self)),
+            ImportSource::Autojump => Ok(dirs.data_dir().join("autojump").join("autojump.txt")),
+            ImportSource::Z => Ok(dirs.home_dir().join(".z")),
+            ImportSource::Fasd => Ok(dirs.home_dir().join(".fasd")),
+        }
+    }
+}
+
+/// Result of parsing a history file
+#[derive(Debug, Default)]
+pub struct ParsedHistory {
+    pub visits: Vec<VisitRecord>,
+    pub skipped: usize,
+}
+
+/// Parse the contents of a history file. Malformed lines are counted and skipped.
+///
+/// zoxide and autojump rows carry no timestamp and get `undated` instead: the history
+/// file's mtime, or 0, so imported rows never look more recent than real visits.
+pub fn parse(source: ImportSource, content: &str, undated: i64) -> ParsedHistory {
+    let mut history = ParsedHistory::default();
+
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        let parsed = match source {
+            ImportSource::Zoxide => {
+                parse_score_line(line.trim_start(), char::is_whitespace, undated)
+            }
+            ImportSource::Autojump => parse_score_line(line, |c| c == '\t', undated),
+            ImportSource::Z | ImportSource::Fasd => parse_pipe_line(line),
+        };
+        match parsed {
+            Some(visit) => history.visits.push(visit),
+            None => history.skipped += 1,
+        }
+    }
+
+    history
+}
+
+/// Weights are floats in every source format; keep at least one visit
+fn weight_to_count(weight: f64) -> i64 {
+    (weight.round() as i64).max(1)
+}
+
+/// `<weight><sep><path>`; these formats carry no timestamp, so use `undated`
+fn parse_score_line(line: &str, sep: impl Fn(char) -> bool, undated: i64) -> Option<VisitRecord> {
+    let (weight, path) = line.split_once(sep)?;
+    let weight: f64 = weight.trim().parse().ok()?;
+    let path = path.trim_start();
+    if !path.starts_with('/') {
+        return None;
+    }
+
+    Some(VisitRecord {
+        path: path.to_string(),
+        access_count: weight_to_count(weight),
+        last_accessed: undated,
+    })
+}
+
+/// `<path>|<rank>|<timestamp>`; paths may themselves contain `|`
+fn parse_pipe_line(line: &str) -> Option<VisitRecord> {
+    let mut fields = line.rsplitn(3, '|');
+    let timestamp: i64 = fields.next()?.trim().parse().ok()?;
+    let rank: f64 = fields.next()?.trim().parse().ok()?;
+    let path = fields.next()?;
+    if !path.starts_with('/') {
+        return None;
+    }
+
+    Some(VisitRecord {
+        path: path.to_string(),
+        access_count: weight_to_count(rank),
+        last_accessed: timestamp,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_zoxide() {
+        let history = parse(
+            ImportSource::Zoxide,
+            "  24.0 /home/user/projects\n   2.5 /tmp/with space\ngarbage\n",
+            0,
+        );
+        assert_eq!(history.visits.len(), 2);
+        assert_eq!(history.skipped, 1);
+        assert_eq!(history.visits[0].path, "/home/user/projects");
+        assert_eq!(history.visits[0].access_count, 24);
+        assert_eq!(history.visits[1].path, "/tmp/with space");
+        assert_eq!(history.visits[0].last_accessed, 0);
+    }
+
+    #[test]
+    fn test_parse_autojump() {
+        let history = parse(
+            ImportSource::Autojump,
+            "22.36\t/home/user/src\n0.2\t/opt\n",
+            1600000000,
+        );
+        assert_eq!(history.visits.len(), 2);
+        assert_eq!(history.visits[0].access_count, 22);
+        assert_eq!(history.visits[1].access_count, 1);
+        assert_eq!(history.visits[1].last_accessed, 1600000000);
+    }
+
+    #[test]
+    fn test_parse_z_and_fasd() {
+        let content = "/home/user/a|b|12|1700000000\n/home/user/c|3.5|1700000100\nbad|line\n";
+        for source in [ImportSource::Z, ImportSource::Fasd] {
+            let history = parse(source, content, 0);
+            assert_eq!(history.visits.len(), 2);
+            assert_eq!(history.skipped, 1);
+            assert_eq!(history.visits[0].path, "/home/user/a|b");
+            assert_eq!(history.visits[0].last_accessed, 1700000000);
+            assert_eq!(history.visits[1].access_count, 4);
+        }
+    }
+
+    #[test]
+    fn test_import_source_from_str() {
+        assert_eq!(
+            "zoxide".parse::<ImportSource>().unwrap(),
+            ImportSource::Zoxide
+        );
+        assert!("fzf".parse::<ImportSource>().is_err());
+    }
+}
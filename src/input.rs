@@ -1,76 +1,40 @@
+//! Terminal key events resolved through the keymap
+
 use anyhow::Result;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{self, Event};
 use std::time::Duration;
 
+use crate::keymap::{Action, Keymap, KeymapMode, Resolved};
+
 pub enum InputEvent {
+    Action(Action),
+    /// Printable key with no binding in the current mode
     Char(char),
-    Escape,
-    Backspace,
-    Enter,
-    ScrollUp,
-    ScrollDown,
-    PageUp,
-    PageDown,
-    GoToStart,
-    GoToEnd,
-    StartSearch,
-    NavigateIn,
-    NavigateOut,
-    ToggleHidden,
-    ToggleFiles,
-    Bookmark,
-    RemoveBookmark,
-    Add,
-    ShowHelp,
     None,
 }
 
-pub fn read_key(timeout_ms: u64) -> Result<InputEvent> {
-    if event::poll(Duration::from_millis(timeout_ms))? {
-        if let Event::Key(KeyEvent {
-            code, modifiers, ..
-        }) = event::read()?
-        {
-            if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('c') {
-                return Ok(InputEvent::Escape);
-            }
-
-            // Ctrl+D for page down
-            if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('d') {
-                return Ok(InputEvent::PageDown);
-            }
-
-            // Ctrl+U for page up
-            if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('u') {
-                return Ok(InputEvent::PageUp);
-            }
+impl From<Resolved> for InputEvent {
+    fn from(resolved: Resolved) -> Self {
+        match resolved {
+            Resolved::Action(action) => InputEvent::Action(action),
+            Resolved::Char(c) => InputEvent::Char(c),
+            Resolved::Pending | Resolved::Unbound => InputEvent::None,
+        }
+    }
+}
 
-            match code {
-                KeyCode::Esc => return Ok(InputEvent::Escape),
-                KeyCode::Backspace => return Ok(InputEvent::Backspace),
-                KeyCode::Enter => return Ok(InputEvent::Enter),
-                KeyCode::Char('/') => return Ok(InputEvent::StartSearch),
-                KeyCode::Char('g') => {
-                    if modifiers.contains(KeyModifiers::SHIFT) {
-                        return Ok(InputEvent::GoToEnd);
-                    }
-                    return Ok(InputEvent::GoToStart);
-                }
-                KeyCode::Char('G') => return Ok(InputEvent::GoToEnd),
-                KeyCode::Char('j') => return Ok(InputEvent::ScrollDown),
-                KeyCode::Char('k') => return Ok(InputEvent::ScrollUp),
-                KeyCode::Char('l') => return Ok(InputEvent::NavigateIn),
-                KeyCode::Char('h') => return Ok(InputEvent::NavigateOut),
-                KeyCode::Char('b') => return Ok(InputEvent::Bookmark),
-                KeyCode::Char('x') => return Ok(InputEvent::RemoveBookmark),
-                KeyCode::Char('a') => return Ok(InputEvent::Add),
-                KeyCode::Char('.') => return Ok(InputEvent::ToggleHidden),
-                KeyCode::Char('f') => return Ok(InputEvent::ToggleFiles),
-                KeyCode::Char('?') => return Ok(InputEvent::ShowHelp),
-                KeyCode::Char(c) => return Ok(InputEvent::Char(c)),
-                _ => {}
-            }
+/// Wait up to `timeout_ms` for a key and resolve it through the keymap for `mode`
+pub fn read_key(keymap: &mut Keymap, mode: KeymapMode, timeout_ms: u64) -> Result<InputEvent> {
+    if let Some(resolved) = keymap.take_replay(mode) {
+        return Ok(resolved.into());
+    }
+    if event::poll(Duration::from_millis(timeout_ms))? {
+        if let Event::Key(key) = event::read()? {
+            return Ok(keymap.feed(mode, key.into()).into());
         }
     }
-    Ok(InputEvent::None)
+    Ok(keymap
+        .expire()
+        .map(InputEvent::from)
+        .unwrap_or(InputEvent::None))
 }
@@ -0,0 +1,612 @@
+//! Key bindings: key sequences mapped to named actions per mode
+//!
+//! Defaults reproduce the built-in Vim-style keys; `[keys.normal]`, `[keys.search]`
+//! and `[keys.prompt]` in config.toml add, replace or remove (`"none"`) bindings.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::ui::Column;
+
+/// How long a key that is bound on its own and also starts a chord waits for the next key
+const CHORD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Something a key sequence can be bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Cancel,
+    Select,
+    DeleteChar,
+    MoveUp,
+    MoveDown,
+    PageUp,
+    PageDown,
+    GoToStart,
+    GoToEnd,
+    StartSearch,
+    NavigateIn,
+    NavigateOut,
+    ToggleHidden,
+    ToggleFiles,
+    ToggleIgnored,
+    ToggleDeep,
+    ToggleColumn(Column),
+    CycleSort,
+    ReverseSort,
+    TogglePreview,
+    Bookmark,
+    RemoveBookmark,
+    RepointBookmark,
+    Create,
+    ShowHelp,
+    CursorLeft,
+    CursorRight,
+    CursorStart,
+    CursorEnd,
+    DeleteForward,
+    DeleteWord,
+    DeleteToStart,
+}
+
+/// Action names as written in config.toml
+const ACTION_NAMES: &[(Action, &str)] = &[
+    (Action::Cancel, "cancel"),
+    (Action::Select, "select"),
+    (Action::DeleteChar, "delete_char"),
+    (Action::MoveUp, "move_up"),
+    (Action::MoveDown, "move_down"),
+    (Action::PageUp, "page_up"),
+    (Action::PageDown, "page_down"),
+    (Action::GoToStart, "go_to_start"),
+    (Action::GoToEnd, "go_to_end"),
+    (Action::StartSearch, "start_search"),
+    (Action::NavigateIn, "navigate_in"),
+    (Action::NavigateOut, "navigate_out"),
+    (Action::ToggleHidden, "toggle_hidden"),
+    (Action::ToggleFiles, "toggle_files"),
+    (Action::ToggleIgnored, "toggle_ignored"),
+    (Action::ToggleDeep, "toggle_deep"),
+    (
+        Action::ToggleColumn(Column::Permissions),
+        "toggle_permissions",
+    ),
+    (Action::ToggleColumn(Column::Owner), "toggle_owner"),
+    (Action::ToggleColumn(Column::Size), "toggle_size"),
+    (Action::ToggleColumn(Column::Items), "toggle_items"),
+    (Action::ToggleColumn(Column::Modified), "toggle_modified"),
+    (Action::CycleSort, "cycle_sort"),
+    (Action::ReverseSort, "reverse_sort"),
+    (Action::TogglePreview, "toggle_preview"),
+    (Action::Bookmark, "bookmark"),
+    (Action::RemoveBookmark, "remove_bookmark"),
+    (Action::RepointBookmark, "repoint_bookmark"),
+    (Action::Create, "create"),
+    (Action::ShowHelp, "help"),
+    (Action::CursorLeft, "cursor_left"),
+    (Action::CursorRight, "cursor_right"),
+    (Action::CursorStart, "cursor_start"),
+    (Action::CursorEnd, "cursor_end"),
+    (Action::DeleteForward, "delete_forward"),
+    (Action::DeleteWord, "delete_word"),
+    (Action::DeleteToStart, "delete_to_start"),
+];
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ACTION_NAMES
+            .iter()
+            .find(|(_, name)| *name == s)
+            .map(|(action, _)| *action)
+            .ok_or_else(|| format!("unknown action '{}'", s))
+    }
+}
+
+/// Binding tables; several TUI modes share one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeymapMode {
+    /// Browsing the list
+    Normal,
+    /// Typing a search query
+    Search,
+    /// Bookmark alias, new entry name and confirmation prompts
+    Prompt,
+}
+
+impl KeymapMode {
+    pub const ALL: [KeymapMode; 3] = [KeymapMode::Normal, KeymapMode::Search, KeymapMode::Prompt];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            KeymapMode::Normal => "normal",
+            KeymapMode::Search => "search",
+            KeymapMode::Prompt => "prompt",
+        }
+    }
+}
+
+/// A single key press with its modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is already part of a character ('G') and of BackTab
+        match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => Self {
+                code: KeyCode::Char(c.to_ascii_uppercase()),
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            KeyCode::BackTab => Self {
+                code,
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            _ => Self { code, modifiers },
+        }
+    }
+
+    /// The character this key types, if it has no Ctrl/Alt modifier
+    pub fn char(&self) -> Option<char> {
+        match self.code {
+            KeyCode::Char(c) if self.modifiers.is_empty() => Some(c),
+            _ => None,
+        }
+    }
+}
+
+impl From<KeyEvent> for Key {
+    fn from(event: KeyEvent) -> Self {
+        Key::new(event.code, event.modifiers)
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    /// Parse `j`, `G`, `ctrl-d`, `alt-enter`, `pagedown`, `f5`, ...
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            let (prefix, modifier) = if lower.starts_with("ctrl-") {
+                ("ctrl-", KeyModifiers::CONTROL)
+            } else if lower.starts_with("alt-") {
+                ("alt-", KeyModifiers::ALT)
+            } else if lower.starts_with("shift-") {
+                ("shift-", KeyModifiers::SHIFT)
+            } else {
+                break;
+            };
+            // `ctrl--` binds Ctrl and the minus key
+            if rest.len() == prefix.len() {
+                break;
+            }
+            modifiers |= modifier;
+            rest = &rest[prefix.len()..];
+        }
+
+        let code = match rest.to_ascii_lowercase().as_str() {
+            "esc" | "escape" => KeyCode::Esc,
+            "enter" | "return" => KeyCode::Enter,
+            "backspace" => KeyCode::Backspace,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            f if f.len() > 1 && f.starts_with('f') && f[1..].parse::<u8>().is_ok() => {
+                KeyCode::F(f[1..].parse().unwrap_or(1))
+            }
+            _ => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(format!("unknown key '{}'", s)),
+                }
+            }
+        };
+        Ok(Key::new(code, modifiers))
+    }
+}
+
+/// Parse a space-separated chord such as `g g`
+fn parse_sequence(keys: &str) -> Result<Vec<Key>, String> {
+    let sequence = keys
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<Vec<Key>, String>>()?;
+    if sequence.is_empty() {
+        return Err("empty key sequence".to_string());
+    }
+    Ok(sequence)
+}
+
+/// Outcome of feeding one key to the keymap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolved {
+    Action(Action),
+    /// An unbound printable key, typed into the current input
+    Char(char),
+    /// Waiting for the rest of a chord
+    Pending,
+    Unbound,
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<KeymapMode, HashMap<Vec<Key>, Action>>,
+    pending: Vec<Key>,
+    pending_mode: KeymapMode,
+    pending_since: Option<Instant>,
+    /// Key that broke a chord, replayed after the chord's own action
+    replay: Option<Key>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut keymap = Keymap::empty();
+        for mode in KeymapMode::ALL {
+            keymap.bind_default(mode, "esc", Action::Cancel);
+            keymap.bind_default(mode, "ctrl-c", Action::Cancel);
+            keymap.bind_default(mode, "enter", Action::Select);
+            keymap.bind_default(mode, "backspace", Action::DeleteChar);
+        }
+        // List movement
+        for mode in [KeymapMode::Normal, KeymapMode::Search] {
+            for (keys, action) in [
+                ("up", Action::MoveUp),
+                ("down", Action::MoveDown),
+                ("tab", Action::MoveDown),
+                ("backtab", Action::MoveUp),
+                ("pageup", Action::PageUp),
+                ("pagedown", Action::PageDown),
+                ("ctrl-d", Action::PageDown),
+            ] {
+                keymap.bind_default(mode, keys, action);
+            }
+        }
+        // Missing entries are only listed in search results
+        keymap.bind_default(KeymapMode::Search, "ctrl-x", Action::RemoveBookmark);
+        keymap.bind_default(KeymapMode::Search, "ctrl-r", Action::RepointBookmark);
+        // Cursor movement and editing in text inputs
+        for mode in [KeymapMode::Search, KeymapMode::Prompt] {
+            for (keys, action) in [
+                ("left", Action::CursorLeft),
+                ("right", Action::CursorRight),
+                ("home", Action::CursorStart),
+                ("end", Action::CursorEnd),
+                ("delete", Action::DeleteForward),
+                ("ctrl-w", Action::DeleteWord),
+                ("ctrl-u", Action::DeleteToStart),
+            ] {
+                keymap.bind_default(mode, keys, action);
+            }
+        }
+        for (keys, action) in [
+            ("ctrl-u", Action::PageUp),
+            ("left", Action::NavigateOut),
+            ("right", Action::NavigateIn),
+            ("home", Action::GoToStart),
+            ("end", Action::GoToEnd),
+            ("j", Action::MoveDown),
+            ("k", Action::MoveUp),
+            ("g", Action::GoToStart),
+            ("G", Action::GoToEnd),
+            ("/", Action::StartSearch),
+            ("l", Action::NavigateIn),
+            ("h", Action::NavigateOut),
+            ("b", Action::Bookmark),
+            ("x", Action::RemoveBookmark),
+            ("R", Action::RepointBookmark),
+            ("a", Action::Create),
+            (".", Action::ToggleHidden),
+            ("f", Action::ToggleFiles),
+            ("i", Action::ToggleIgnored),
+            ("r", Action::ToggleDeep),
+            ("c p", Action::ToggleColumn(Column::Permissions)),
+            ("c o", Action::ToggleColumn(Column::Owner)),
+            ("c s", Action::ToggleColumn(Column::Size)),
+            ("c n", Action::ToggleColumn(Column::Items)),
+            ("c m", Action::ToggleColumn(Column::Modified)),
+            ("s", Action::CycleSort),
+            ("S", Action::ReverseSort),
+            ("p", Action::TogglePreview),
+            ("?", Action::ShowHelp),
+        ] {
+            keymap.bind_default(KeymapMode::Normal, keys, action);
+        }
+        keymap
+    }
+}
+
+impl Keymap {
+    /// A keymap with no bindings; every printable key types itself
+    pub fn empty() -> Self {
+        Self {
+            bindings: HashMap::new(),
+            pending: Vec::new(),
+            pending_mode: KeymapMode::Normal,
+            pending_since: None,
+            replay: None,
+        }
+    }
+
+    fn bind_default(&mut self, mode: KeymapMode, keys: &str, action: Action) {
+        if let Ok(sequence) = parse_sequence(keys) {
+            self.bindings
+                .entry(mode)
+                .or_default()
+                .insert(sequence, action);
+        }
+    }
+
+    /// Bind `keys` (e.g. `ctrl-d` or `g g`) to the action named `action`; `none` unbinds
+    pub fn bind(&mut self, mode: KeymapMode, keys: &str, action: &str) -> Result<(), String> {
+        let sequence = parse_sequence(keys)?;
+        let table = self.bindings.entry(mode).or_default();
+        if action == "none" {
+            table.remove(&sequence);
+        } else {
+            table.insert(sequence, action.parse()?);
+        }
+        Ok(())
+    }
+
+    /// Resolve the next key press
+    pub fn feed(&mut self, mode: KeymapMode, key: Key) -> Resolved {
+        if mode != self.pending_mode {
+            self.pending.clear();
+            self.pending_mode = mode;
+        }
+        self.pending.push(key);
+
+        let Some(table) = self.bindings.get(&mode) else {
+            return self.flush(mode);
+        };
+        let extends = table
+            .keys()
+            .any(|seq| seq.len() > self.pending.len() && seq.starts_with(&self.pending));
+        if extends {
+            self.pending_since = Some(Instant::now());
+            return Resolved::Pending;
+        }
+        if table.contains_key(&self.pending) || self.pending.len() == 1 {
+            return self.flush(mode);
+        }
+
+        // The chord broke: resolve what came before and replay the new key next
+        self.pending.pop();
+        self.replay = Some(key);
+        self.flush(mode)
+    }
+
+    /// A key held back by a broken chord, resolved in the (possibly new) current mode
+    pub fn take_replay(&mut self, mode: KeymapMode) -> Option<Resolved> {
+        let key = self.replay.take()?;
+        Some(self.feed(mode, key))
+    }
+
+    /// Resolve a pending chord prefix once the user stops typing
+    pub fn expire(&mut self) -> Option<Resolved> {
+        let since = self.pending_since?;
+        if since.elapsed() < CHORD_TIMEOUT {
+            return None;
+        }
+        Some(self.flush(self.pending_mode))
+    }
+
+    /// Resolve a pending chord prefix now, e.g. when scripted input runs out
+    pub fn flush_pending(&mut self) -> Option<Resolved> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        Some(self.flush(self.pending_mode))
+    }
+
+    /// Resolve and clear the pending keys on their own
+    fn flush(&mut self, mode: KeymapMode) -> Resolved {
+        let keys = std::mem::take(&mut self.pending);
+        self.pending_since = None;
+        if let Some(action) = self.bindings.get(&mode).and_then(|t| t.get(&keys)) {
+            return Resolved::Action(*action);
+        }
+        match keys.as_slice() {
+            [key] => key.char().map(Resolved::Char).unwrap_or(Resolved::Unbound),
+            _ => Resolved::Unbound,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(s: &str) -> Key {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(
+            key("ctrl-d"),
+            Key::new(KeyCode::Char('d'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(key("G"), Key::new(KeyCode::Char('g'), KeyModifiers::SHIFT));
+        assert_eq!(
+            key("PageDown"),
+            Key::new(KeyCode::PageDown, KeyModifiers::NONE)
+        );
+        assert_eq!(key("f5"), Key::new(KeyCode::F(5), KeyModifiers::NONE));
+        assert_eq!(key("f"), Key::new(KeyCode::Char('f'), KeyModifiers::NONE));
+        assert_eq!(
+            key("ctrl--"),
+            Key::new(KeyCode::Char('-'), KeyModifiers::CONTROL)
+        );
+        assert!("ctrl-bogus".parse::<Key>().is_err());
+    }
+
+    #[test]
+    fn test_default_bindings_per_mode() {
+        let mut keymap = Keymap::default();
+        assert_eq!(
+            keymap.feed(KeymapMode::Normal, key("j")),
+            Resolved::Action(Action::MoveDown)
+        );
+        // Text modes type the same key
+        assert_eq!(
+            keymap.feed(KeymapMode::Search, key("j")),
+            Resolved::Char('j')
+        );
+        assert_eq!(
+            keymap.feed(KeymapMode::Search, key("ctrl-d")),
+            Resolved::Action(Action::PageDown)
+        );
+        assert_eq!(
+            keymap.feed(KeymapMode::Prompt, key("esc")),
+            Resolved::Action(Action::Cancel)
+        );
+        assert_eq!(
+            keymap.feed(KeymapMode::Normal, key("3")),
+            Resolved::Char('3')
+        );
+    }
+
+    #[test]
+    fn test_special_keys_per_mode() {
+        let mut keymap = Keymap::default();
+        let mut resolve = |mode, k: &str| keymap.feed(mode, key(k));
+
+        assert_eq!(
+            resolve(KeymapMode::Normal, "left"),
+            Resolved::Action(Action::NavigateOut)
+        );
+        assert_eq!(
+            resolve(KeymapMode::Normal, "end"),
+            Resolved::Action(Action::GoToEnd)
+        );
+        assert_eq!(
+            resolve(KeymapMode::Search, "down"),
+            Resolved::Action(Action::MoveDown)
+        );
+        assert_eq!(
+            resolve(KeymapMode::Search, "end"),
+            Resolved::Action(Action::CursorEnd)
+        );
+        assert_eq!(
+            resolve(KeymapMode::Normal, "ctrl-u"),
+            Resolved::Action(Action::PageUp)
+        );
+        assert_eq!(
+            resolve(KeymapMode::Search, "ctrl-u"),
+            Resolved::Action(Action::DeleteToStart)
+        );
+        assert_eq!(
+            resolve(KeymapMode::Prompt, "ctrl-w"),
+            Resolved::Action(Action::DeleteWord)
+        );
+        assert_eq!(resolve(KeymapMode::Prompt, "tab"), Resolved::Unbound);
+    }
+
+    #[test]
+    fn test_rebind_and_unbind() {
+        let mut keymap = Keymap::default();
+        keymap
+            .bind(KeymapMode::Normal, "d", "remove_bookmark")
+            .unwrap();
+        keymap
+            .bind(KeymapMode::Normal, "left", "navigate_out")
+            .unwrap();
+        keymap.bind(KeymapMode::Normal, "h", "none").unwrap();
+
+        assert_eq!(
+            keymap.feed(KeymapMode::Normal, key("d")),
+            Resolved::Action(Action::RemoveBookmark)
+        );
+        assert_eq!(
+            keymap.feed(KeymapMode::Normal, key("left")),
+            Resolved::Action(Action::NavigateOut)
+        );
+        assert_eq!(
+            keymap.feed(KeymapMode::Normal, key("h")),
+            Resolved::Char('h')
+        );
+
+        assert!(keymap.bind(KeymapMode::Normal, "d", "delete").is_err());
+        assert!(keymap.bind(KeymapMode::Normal, "", "help").is_err());
+    }
+
+    #[test]
+    fn test_chords() {
+        let mut keymap = Keymap::default();
+        keymap.bind(KeymapMode::Normal, "g", "none").unwrap();
+        keymap
+            .bind(KeymapMode::Normal, "g g", "go_to_start")
+            .unwrap();
+        keymap
+            .bind(KeymapMode::Normal, "space f", "toggle_files")
+            .unwrap();
+
+        assert_eq!(keymap.feed(KeymapMode::Normal, key("g")), Resolved::Pending);
+        assert_eq!(
+            keymap.feed(KeymapMode::Normal, key("g")),
+            Resolved::Action(Action::GoToStart)
+        );
+
+        assert_eq!(
+            keymap.feed(KeymapMode::Normal, key("space")),
+            Resolved::Pending
+        );
+        assert_eq!(
+            keymap.feed(KeymapMode::Normal, key("f")),
+            Resolved::Action(Action::ToggleFiles)
+        );
+
+        // A key that breaks the chord is replayed on its own
+        assert_eq!(keymap.feed(KeymapMode::Normal, key("g")), Resolved::Pending);
+        assert_eq!(
+            keymap.feed(KeymapMode::Normal, key("j")),
+            Resolved::Char('g')
+        );
+        assert_eq!(
+            keymap.take_replay(KeymapMode::Normal),
+            Some(Resolved::Action(Action::MoveDown))
+        );
+        assert_eq!(keymap.take_replay(KeymapMode::Normal), None);
+    }
+
+    #[test]
+    fn test_ambiguous_prefix_fires_on_break() {
+        let mut keymap = Keymap::default();
+        keymap
+            .bind(KeymapMode::Normal, "g g", "toggle_hidden")
+            .unwrap();
+
+        // `g` alone still goes to the start once the chord is abandoned
+        assert_eq!(keymap.feed(KeymapMode::Normal, key("g")), Resolved::Pending);
+        assert_eq!(
+            keymap.feed(KeymapMode::Normal, key("k")),
+            Resolved::Action(Action::GoToStart)
+        );
+        assert_eq!(
+            keymap.take_replay(KeymapMode::Normal),
+            Some(Resolved::Action(Action::MoveUp))
+        );
+        assert_eq!(keymap.expire(), None);
+    }
+}
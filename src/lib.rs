@@ -1,2 +1,46 @@
+//! jump: a Vim-style directory navigator
+//!
+//! The `jump` binary is a thin wrapper around this crate. [`pick_directory`] opens the
+//! navigator and returns what the user picked; the parts it is built from are public
+//! for tools that embed or script it:
+//!
+//! - [`Database`]: bookmarks and visit history (SQLite)
+//! - [`fs::walk_tree`]: directory listing with ignore files, as the picker shows it
+//! - [`fs::scan_directories`]: single-level directory listing
+//! - [`FuzzyMatchEngine`]: fuzzy scoring
+//! - [`FuzzyState`]: listing, query and selection behind the TUI
+//! - [`preview::Previewer`]: directory and text previews built off the UI thread
+//! - [`App`]: key actions to state changes and [`app::Effect`]s
+//! - [`shell`]: init scripts and completions
+//!
+//! ```no_run
+//! use std::path::Path;
+//!
+//! let options = jump::PickOptions::default();
+//! if let Some(dir) = jump::pick_directory(Path::new("."), &options)? {
+//!     println!("{}", dir.display());
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+pub mod app;
+pub mod bookmarks;
+pub mod config;
+pub mod database;
+pub mod driver;
 pub mod fs;
 pub mod fuzzy;
+pub mod import;
+pub mod input;
+pub mod keymap;
+pub mod preview;
+pub mod query;
+pub mod shell;
+pub mod ui;
+
+pub use app::App;
+pub use config::settings::Settings;
+pub use database::Database;
+pub use driver::{pick_directory, PickOptions};
+pub use fuzzy::FuzzyMatchEngine;
+pub use ui::FuzzyState;
@@ -1,48 +1,183 @@
-mod config;
-mod database;
-mod fs;
-mod fuzzy;
-mod input;
-mod shell;
-mod ui;
-
 use anyhow::{Context, Result};
-use crossterm::{
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use ratatui::{backend::CrosstermBackend, Terminal};
-use std::{env, fs::File, io, panic};
+use std::{env, io};
+
+use jump::config::settings::{self, Settings};
+use jump::config::{self, parse_args, Command, ConfigAction};
+use jump::database::{self, BookmarkError, Database};
+use jump::{bookmarks, driver, fs, fuzzy, import, query, shell, PickOptions};
 
-use config::{parse_args, ParseResult};
-use database::Database;
-use input::InputEvent;
-use ui::FuzzyState;
+fn main() -> Result<()> {
+    let command = match parse_args() {
+        Ok(command) => command,
+        Err(e) => {
+            config::print_error(&e);
+            std::process::exit(2);
+        }
+    };
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum Mode {
-    Normal,
-    Search,
-    BookmarkInput(String),
-    BookmarkRemove,
-    CreateEntry(String),
-    ShowHelp,
+    match command {
+        Command::Run(config) if config.no_tui => run_query(config),
+        Command::Run(config) if config.script.is_some() => run_script(config),
+        Command::Run(config) => run(config),
+        Command::Bookmark(action) => handle_bookmark_action(action),
+        Command::Init(shell) => Ok(shell::print_shell_init(shell, &load_settings().editor)?),
+        Command::Completions(shell) => Ok(shell::print_completion(shell, config::NAME)?),
+        Command::Import { source, file } => run_import(source, file),
+        Command::Prune { dry_run, bookmarks } => run_prune(dry_run, bookmarks),
+        Command::Config(action) => run_config(action),
+        Command::Help(topic) => {
+            config::print_help(topic);
+            Ok(())
+        }
+        Command::Version => {
+            println!("{} {}", config::NAME, config::VERSION);
+            Ok(())
+        }
+    }
 }
 
-fn main() -> Result<()> {
-    let (result, bookmark_action) = parse_args();
+/// Settings from config.toml and `JUMP_*`; invalid values are reported like CLI errors
+fn load_settings() -> Settings {
+    settings::load().unwrap_or_else(|e| {
+        eprintln!("{}: {}", config::NAME, e);
+        std::process::exit(2);
+    })
+}
+
+fn run_config(action: ConfigAction) -> Result<()> {
+    match action {
+        ConfigAction::Show => print!("{}", load_settings().to_toml()),
+        ConfigAction::PrintDefaults => print!("{}", settings::DEFAULT_CONFIG),
+        ConfigAction::Path => match settings::config_path() {
+            Some(path) => println!("{}", path.display()),
+            None => anyhow::bail!("Failed to determine the config directory"),
+        },
+    }
+    Ok(())
+}
+
+/// Remove entries for missing directories; bookmarks only when `bookmarks` is set
+fn run_prune(dry_run: bool, bookmarks: bool) -> Result<()> {
+    let db = Database::new()?;
+    let stale = db.find_stale()?;
+
+    if stale.is_empty() {
+        println!("Nothing to prune.");
+        return Ok(());
+    }
+
+    for entry in &stale {
+        match entry.bookmark_key {
+            Some(ref key) if !bookmarks => println!("  [{}] {} (missing, kept)", key, entry.path),
+            Some(ref key) => println!("  [{}] {} (missing)", key, entry.path),
+            None => println!("  {} (missing)", entry.path),
+        }
+    }
+
+    let (removable, kept): (Vec<_>, Vec<_>) =
+        stale.into_iter().partition(|e| bookmarks || !e.is_bookmark);
+    if dry_run {
+        println!(
+            "{} stale entries (dry run, nothing removed)",
+            removable.len() + kept.len()
+        );
+    } else {
+        let paths: Vec<String> = removable.into_iter().map(|e| e.path).collect();
+        let removed = db.remove_entries(&paths)?;
+        println!("Removed {} stale entries", removed);
+    }
+    if !kept.is_empty() {
+        println!(
+            "Kept {} stale bookmarks: remove them with 'jump prune --bookmarks', or repoint one \
+             with 'jump bookmark add <key> <path> --force' (R in the TUI).",
+            kept.len()
+        );
+    }
+    Ok(())
+}
 
-    match bookmark_action {
-        config::BookmarkAction::None => {}
-        action => {
-            handle_bookmark_action(action)?;
-            return Ok(());
+fn run_import(source: import::ImportSource, file: Option<String>) -> Result<()> {
+    let path = match file.as_deref() {
+        Some("-") => None,
+        Some(path) => Some(std::path::PathBuf::from(path)),
+        None => Some(source.default_file()?),
+    };
+    let (content, modified) = match &path {
+        None => (io::read_to_string(io::stdin())?, 0),
+        Some(path) => {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            (content, file_mtime(path))
         }
+    };
+
+    let history = import::parse(source, &content, modified);
+    let db = Database::new()?;
+    let imported = db.import_visits(&history.visits)?;
+
+    println!("Imported {} entries", imported);
+    if history.skipped > 0 {
+        eprintln!("Skipped {} malformed lines", history.skipped);
     }
+    Ok(())
+}
+
+/// Modification time in Unix seconds, or 0 if it can't be read
+fn file_mtime(path: &std::path::Path) -> i64 {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |elapsed| elapsed.as_secs() as i64)
+}
 
-    match result {
-        ParseResult::Exit => Ok(()),
-        ParseResult::Config(config) => run(config),
+fn run_query(config: config::Config) -> Result<()> {
+    let mut settings = load_settings();
+    settings.merge_cli(&config);
+    let Some(pattern) = config.query else {
+        anyhow::bail!("query mode requires a search term (e.g. 'jump query proj')");
+    };
+
+    let db = Database::new()?;
+    let now = database::db::now_secs();
+    let current_dir = env::current_dir()?;
+
+    let mut candidates: Vec<query::Candidate> = db
+        .get_all_bookmarks()?
+        .iter()
+        .chain(db.get_frecent(driver::FRECENT_LIMIT)?.iter())
+        .map(|entry| query::Candidate::from_db(entry, now))
+        .collect();
+    // Same listing as the picker would show, ignore files and `exclude` included
+    let options = fs::WalkOptions {
+        max_depth: if settings.deep_search {
+            settings.search_depth
+        } else {
+            1
+        },
+        show_hidden: settings.show_hidden,
+        show_files: false,
+        show_ignored: settings.show_ignored,
+        exclude: settings.exclude.clone(),
+        read_metadata: false,
+    };
+    fs::walk_tree(&current_dir, &options, |batch| {
+        candidates.extend(batch.iter().map(query::Candidate::from_fs));
+        true
+    });
+    candidates.retain(|c| std::path::Path::new(&c.path).is_dir());
+
+    let matcher = fuzzy::FuzzyMatchEngine::new();
+    match query::best_match(&matcher, &pattern, candidates) {
+        Some(path) => {
+            let _ = db.record_visit(&path);
+            println!("{}", path);
+            Ok(())
+        }
+        None => {
+            eprintln!("jump: no match for '{}'", pattern);
+            std::process::exit(1);
+        }
     }
 }
 
@@ -50,9 +185,21 @@ fn handle_bookmark_action(action: config::BookmarkAction) -> Result<()> {
     let db = Database::new()?;
 
     match action {
-        config::BookmarkAction::Add { key, name, path } => {
+        config::BookmarkAction::Add {
+            key,
+            name,
+            path,
+            force,
+            resolve_symlinks,
+        } => {
             let current_dir = env::current_dir()?;
-            let target_path = path.unwrap_or_else(|| current_dir.to_string_lossy().into_owned());
+            let target_path = fs::normalize_path(
+                path.as_deref().unwrap_or("."),
+                &current_dir,
+                resolve_symlinks,
+            )
+            .to_string_lossy()
+            .into_owned();
             let target_name = name.unwrap_or_else(|| {
                 std::path::Path::new(&target_path)
                     .file_name()
@@ -60,7 +207,19 @@ fn handle_bookmark_action(action: config::BookmarkAction) -> Result<()> {
                     .unwrap_or(&target_path)
                     .to_string()
             });
-            db.set_bookmark(&target_path, &target_name, &key)?;
+            if force {
+                db.overwrite_bookmark(&target_path, &target_name, &key)?;
+            } else if let Err(e) = db.set_bookmark(&target_path, &target_name, &key) {
+                if let BookmarkError::KeyConflict { .. } = e {
+                    eprintln!("{}", e);
+                    eprintln!(
+                        "Use 'jump bookmark add {} ... --force' to overwrite it.",
+                        key
+                    );
+                    std::process::exit(1);
+                }
+                return Err(e.into());
+            }
             println!("Added bookmark '{}' -> {}", key, target_path);
         }
         config::BookmarkAction::Remove { key } => {
@@ -70,7 +229,7 @@ fn handle_bookmark_action(action: config::BookmarkAction) -> Result<()> {
         config::BookmarkAction::List => {
             let bookmarks = db.get_all_bookmarks()?;
             if bookmarks.is_empty() {
-                println!("No bookmarks set. Use 'jump --bookmark add <key> <path>' to add one.");
+                println!("No bookmarks set. Use 'jump bookmark add <key> <path>' to add one.");
             } else {
                 println!("Bookmarks:");
                 for bookmark in bookmarks {
@@ -87,316 +246,93 @@ fn handle_bookmark_action(action: config::BookmarkAction) -> Result<()> {
                 std::process::exit(1);
             }
         }
-        config::BookmarkAction::None => {}
+        config::BookmarkAction::Export { format } => {
+            let home = home_dir()?;
+            let bookmarks = db.get_all_bookmarks()?;
+            println!("{}", bookmarks::export(&bookmarks, format, &home)?);
+        }
+        config::BookmarkAction::Import {
+            file,
+            format,
+            replace,
+        } => {
+            let home = home_dir()?;
+            let format = format.unwrap_or_else(|| {
+                bookmarks::BookmarkFormat::from_path(std::path::Path::new(&file))
+            });
+            let content = std::fs::read_to_string(&file)
+                .with_context(|| format!("Failed to read {}", file))?;
+            let records: Vec<(String, String, String)> = bookmarks::parse(&content, format, &home)?
+                .into_iter()
+                .map(|b| {
+                    // Paths in the file that are still relative are taken from $HOME
+                    let path = fs::normalize_path(&b.path, &home, false);
+                    (path.to_string_lossy().into_owned(), b.name, b.key)
+                })
+                .collect();
+            let count = db.import_bookmarks(&records, replace)?;
+            println!("Imported {} bookmarks", count);
+        }
     }
 
     Ok(())
 }
 
-fn run(config: config::Config) -> Result<()> {
-    let current_dir = env::current_dir()?;
-
-    setup_panic_hook();
-
-    let tty = File::options()
-        .read(true)
-        .write(true)
-        .open("/dev/tty")
-        .context("Failed to open /dev/tty - are you running in a terminal?")?;
-    let mut tty_output = tty.try_clone()?;
-
-    enable_raw_mode()?;
-    execute!(tty_output, EnterAlternateScreen)?;
-
-    let backend = CrosstermBackend::new(tty_output);
-    let mut terminal = Terminal::new(backend)?;
-
-    run_fuzzy_mode(
-        &mut terminal,
-        &current_dir,
-        config.show_hidden,
-        config.query.as_deref(),
-    )?;
+fn home_dir() -> Result<std::path::PathBuf> {
+    Ok(directories::BaseDirs::new()
+        .context("Failed to determine home directory")?
+        .home_dir()
+        .to_path_buf())
+}
 
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+fn run(config: config::Config) -> Result<()> {
+    let mut settings = load_settings();
+    settings.merge_cli(&config);
+    let options = PickOptions {
+        settings,
+        query: config.query,
+        use_database: true,
+    };
 
+    if let Some(path) = jump::pick_directory(&env::current_dir()?, &options)? {
+        println!("{}", path.display());
+    }
     Ok(())
 }
 
-fn run_fuzzy_mode(
-    terminal: &mut Terminal<CrosstermBackend<File>>,
-    start_dir: &std::path::PathBuf,
-    show_hidden: bool,
-    query: Option<&str>,
-) -> Result<()> {
-    let db = Database::new().ok();
-    let mut fuzzy_state = FuzzyState::new_in_dir(start_dir, show_hidden);
-
-    // Load bookmarks from DB
-    if let Some(ref db) = db {
-        if let Ok(bookmarks) = db.get_all_bookmarks() {
-            fuzzy_state.set_bookmarks(bookmarks);
+/// `jump --script KEYS`: run the TUI headlessly, print the last frame to stderr and any
+/// selection to stdout. The run gets an empty in-memory database, so its output doesn't
+/// depend on the user's history and its visits and bookmarks aren't kept.
+fn run_script(config: config::Config) -> Result<()> {
+    let keys = match driver::parse_script(config.script.as_deref().unwrap_or_default()) {
+        Ok(keys) => keys,
+        Err(e) => {
+            eprintln!("jump: {e}");
+            std::process::exit(2);
         }
-    }
+    };
+    let mut settings = load_settings();
+    settings.merge_cli(&config);
 
-    if let Some(q) = query {
-        fuzzy_state.set_query(q);
-    }
-
-    let mut mode = Mode::Normal;
-
-    loop {
-        terminal.draw(|f| ui::draw_fuzzy(f, &fuzzy_state, &mode))?;
+    let db = Database::open_in_memory().ok();
+    let current_dir = env::current_dir()?;
+    let mut app = driver::load_app(
+        &current_dir,
+        &settings,
+        db.as_ref(),
+        config.query.as_deref(),
+    );
+    let outcome = driver::run_script(
+        &mut app,
+        &mut settings.keymap(),
+        db.as_ref(),
+        &keys,
+        driver::SCRIPT_SIZE,
+    )?;
 
-        match input::read_key(100)? {
-            InputEvent::StartSearch => match mode {
-                Mode::Normal => mode = Mode::Search,
-                Mode::Search => fuzzy_state.add_char('/'),
-                Mode::BookmarkInput(ref mut alias) => alias.push('/'),
-                Mode::BookmarkRemove => {}
-                Mode::CreateEntry(ref mut name) => name.push('/'),
-                Mode::ShowHelp => mode = Mode::Normal,
-            },
-            InputEvent::Escape => match mode {
-                Mode::Search => {
-                    mode = Mode::Normal;
-                    fuzzy_state.clear_query();
-                }
-                Mode::BookmarkInput(_) | Mode::BookmarkRemove | Mode::CreateEntry(_) | Mode::ShowHelp => {
-                    mode = Mode::Normal;
-                }
-                Mode::Normal => {
-                    return Ok(());
-                }
-            },
-            InputEvent::Enter => match mode {
-                Mode::BookmarkInput(ref alias) => {
-                    if !alias.is_empty() {
-                        if let Some(item) = fuzzy_state.selected_item() {
-                            let path = item.path();
-                            let name = item.entry.name.clone();
-                            if let Some(ref db) = db {
-                                let _ = db.set_bookmark(&path, &name, alias);
-                                if let Ok(bookmarks) = db.get_all_bookmarks() {
-                                    fuzzy_state.set_bookmarks(bookmarks);
-                                    fuzzy_state.refilter();
-                                }
-                            }
-                        }
-                    }
-                    mode = Mode::Normal;
-                }
-                Mode::BookmarkRemove => {
-                    if let Some(item) = fuzzy_state.selected_item() {
-                        if let Some(ref key) = item.bookmark_key {
-                            if let Some(ref db) = db {
-                                let _ = db.remove_bookmark(key);
-                                if let Ok(bookmarks) = db.get_all_bookmarks() {
-                                    fuzzy_state.set_bookmarks(bookmarks);
-                                    fuzzy_state.refilter();
-                                }
-                            }
-                        }
-                    }
-                    mode = Mode::Normal;
-                }
-                Mode::CreateEntry(ref name) => {
-                    if !name.is_empty() {
-                        let target = fuzzy_state.current_dir.join(name.trim_end_matches('/'));
-                        let result = if name.ends_with('/') {
-                            std::fs::create_dir_all(&target).map_err(anyhow::Error::from)
-                        } else {
-                            // create parent dirs if needed, then create file
-                            if let Some(parent) = target.parent() {
-                                std::fs::create_dir_all(parent)?;
-                            }
-                            std::fs::File::create(&target).map(|_| ()).map_err(anyhow::Error::from)
-                        };
-                        if let Err(e) = result {
-                            eprintln!("jump: create failed: {e}");
-                        }
-                        fuzzy_state.reload();
-                    }
-                    mode = Mode::Normal;
-                }
-                _ => {
-                    if let Some(item) = fuzzy_state.selected_item() {
-                        println!("{}", item.path());
-                        return Ok(());
-                    } else {
-                        // If no items (e.g., empty directory), select current directory
-                        println!("{}", fuzzy_state.current_dir.display());
-                        return Ok(());
-                    }
-                }
-            },
-            InputEvent::NavigateIn => match mode {
-                Mode::Search => fuzzy_state.add_char('l'),
-                Mode::BookmarkInput(ref mut alias) => alias.push('l'),
-                Mode::Normal => fuzzy_state.navigate_into(),
-                Mode::BookmarkRemove => {}
-                Mode::CreateEntry(ref mut name) => name.push('l'),
-                Mode::ShowHelp => mode = Mode::Normal,
-            },
-            InputEvent::NavigateOut => match mode {
-                Mode::Search => fuzzy_state.add_char('h'),
-                Mode::BookmarkInput(ref mut alias) => alias.push('h'),
-                Mode::Normal => fuzzy_state.navigate_back(),
-                Mode::BookmarkRemove => {}
-                Mode::CreateEntry(ref mut name) => name.push('h'),
-                Mode::ShowHelp => mode = Mode::Normal,
-            },
-            InputEvent::Bookmark => match mode {
-                Mode::Normal => {
-                    if fuzzy_state.selected_item().is_some() {
-                        mode = Mode::BookmarkInput(String::new());
-                    }
-                }
-                Mode::Search => fuzzy_state.add_char('b'),
-                Mode::BookmarkInput(ref mut alias) => alias.push('b'),
-                Mode::BookmarkRemove => {}
-                Mode::CreateEntry(ref mut name) => name.push('b'),
-                Mode::ShowHelp => mode = Mode::Normal,
-            },
-            InputEvent::RemoveBookmark => match mode {
-                Mode::Normal => {
-                    if let Some(item) = fuzzy_state.selected_item() {
-                        if item.is_bookmark {
-                            mode = Mode::BookmarkRemove;
-                        }
-                    }
-                }
-                Mode::Search => fuzzy_state.add_char('x'),
-                Mode::BookmarkInput(ref mut alias) => alias.push('x'),
-                Mode::BookmarkRemove => {}
-                Mode::CreateEntry(ref mut name) => name.push('x'),
-                Mode::ShowHelp => mode = Mode::Normal,
-            },
-            InputEvent::Char(c) => match mode {
-                Mode::Search => fuzzy_state.add_char(c),
-                Mode::BookmarkInput(ref mut alias) => alias.push(c),
-                Mode::Normal => {
-                    if c == '/' {
-                        mode = Mode::Search;
-                    } else if c.is_ascii_digit() {
-                        let digit = c.to_digit(10).unwrap() as usize;
-                        let new_count = fuzzy_state.motion_count.unwrap_or(0) * 10 + digit;
-                        fuzzy_state.set_motion_count(new_count);
-                    }
-                }
-                Mode::BookmarkRemove => {}
-                Mode::CreateEntry(ref mut name) => name.push(c),
-                Mode::ShowHelp => mode = Mode::Normal,
-            },
-            InputEvent::Backspace => match mode {
-                Mode::Search => {
-                    fuzzy_state.pop_char();
-                    if fuzzy_state.search_query.is_empty() {
-                        mode = Mode::Normal;
-                    }
-                }
-                Mode::BookmarkInput(ref mut alias) => {
-                    alias.pop();
-                }
-                Mode::CreateEntry(ref mut name) => {
-                    name.pop();
-                }
-                Mode::Normal | Mode::BookmarkRemove | Mode::ShowHelp => {}
-            },
-            InputEvent::ScrollUp => match mode {
-                Mode::Search => fuzzy_state.add_char('k'),
-                Mode::BookmarkInput(ref mut alias) => alias.push('k'),
-                Mode::Normal => {
-                    let count = fuzzy_state.take_motion_count();
-                    for _ in 0..count {
-                        fuzzy_state.move_up();
-                    }
-                }
-                Mode::BookmarkRemove => {}
-                Mode::CreateEntry(ref mut name) => name.push('k'),
-                Mode::ShowHelp => mode = Mode::Normal,
-            },
-            InputEvent::ScrollDown => match mode {
-                Mode::Search => fuzzy_state.add_char('j'),
-                Mode::BookmarkInput(ref mut alias) => alias.push('j'),
-                Mode::Normal => {
-                    let count = fuzzy_state.take_motion_count();
-                    for _ in 0..count {
-                        fuzzy_state.move_down();
-                    }
-                }
-                Mode::BookmarkRemove => {}
-                Mode::CreateEntry(ref mut name) => name.push('j'),
-                Mode::ShowHelp => mode = Mode::Normal,
-            },
-            InputEvent::GoToStart => match mode {
-                Mode::Search => fuzzy_state.add_char('g'),
-                Mode::BookmarkInput(ref mut alias) => alias.push('g'),
-                Mode::Normal => fuzzy_state.go_to_start(),
-                Mode::BookmarkRemove => {}
-                Mode::CreateEntry(ref mut name) => name.push('g'),
-                Mode::ShowHelp => mode = Mode::Normal,
-            },
-            InputEvent::GoToEnd => match mode {
-                Mode::Search => fuzzy_state.add_char('G'),
-                Mode::BookmarkInput(ref mut alias) => alias.push('G'),
-                Mode::Normal => fuzzy_state.go_to_end(),
-                Mode::BookmarkRemove => {}
-                Mode::CreateEntry(ref mut name) => name.push('G'),
-                Mode::ShowHelp => mode = Mode::Normal,
-            },
-            InputEvent::PageUp => match mode {
-                Mode::Normal | Mode::Search => fuzzy_state.page_up(),
-                Mode::BookmarkInput(_) | Mode::BookmarkRemove | Mode::CreateEntry(_) | Mode::ShowHelp => {}
-            },
-            InputEvent::PageDown => match mode {
-                Mode::Normal | Mode::Search => fuzzy_state.page_down(),
-                Mode::BookmarkInput(_) | Mode::BookmarkRemove | Mode::CreateEntry(_) | Mode::ShowHelp => {}
-            },
-            InputEvent::ToggleHidden => match mode {
-                Mode::Search => fuzzy_state.add_char('.'),
-                Mode::BookmarkInput(ref mut alias) => alias.push('.'),
-                Mode::Normal => fuzzy_state.toggle_hidden(),
-                Mode::BookmarkRemove => {}
-                Mode::CreateEntry(ref mut name) => name.push('.'),
-                Mode::ShowHelp => mode = Mode::Normal,
-            },
-            InputEvent::ToggleFiles => match mode {
-                Mode::Search => fuzzy_state.add_char('f'),
-                Mode::BookmarkInput(ref mut alias) => alias.push('f'),
-                Mode::Normal => fuzzy_state.toggle_files(),
-                Mode::BookmarkRemove => {}
-                Mode::CreateEntry(ref mut name) => name.push('f'),
-                Mode::ShowHelp => mode = Mode::Normal,
-            },
-            InputEvent::Add => match mode {
-                Mode::Normal => mode = Mode::CreateEntry(String::new()),
-                Mode::Search => fuzzy_state.add_char('a'),
-                Mode::BookmarkInput(ref mut alias) => alias.push('a'),
-                Mode::BookmarkRemove => {}
-                Mode::CreateEntry(ref mut name) => name.push('a'),
-                Mode::ShowHelp => {}
-            },
-            InputEvent::ShowHelp => match mode {
-                Mode::Normal => mode = Mode::ShowHelp,
-                Mode::ShowHelp => mode = Mode::Normal,
-                Mode::Search => fuzzy_state.add_char('?'),
-                Mode::BookmarkInput(ref mut alias) => alias.push('?'),
-                Mode::BookmarkRemove => {}
-                Mode::CreateEntry(ref mut name) => name.push('?'),
-            },
-            InputEvent::None => {}
-        }
+    eprintln!("{}", outcome.screen);
+    if let Some(path) = outcome.selection {
+        println!("{}", path);
     }
-}
-
-fn setup_panic_hook() {
-    let original_hook = panic::take_hook();
-    panic::set_hook(Box::new(move |panic_info| {
-        let _ = disable_raw_mode();
-        let _ = execute!(io::stdout(), LeaveAlternateScreen);
-        original_hook(panic_info);
-    }));
+    Ok(())
 }
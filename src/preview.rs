@@ -0,0 +1,263 @@
+//! Previews of the selected entry for the TUI's side pane
+//!
+//! A [`Previewer`] builds them on a background thread so reading a large directory or a
+//! slow file never blocks drawing; when the selection moves faster than previews can be
+//! built, only the newest request is served.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+
+use crate::fs::EntryMeta;
+use crate::ui::natural_cmp;
+
+/// Lines of a file, or children of a directory, kept for the pane
+pub const PREVIEW_LINES: usize = 200;
+/// Bytes read from the start of a file
+const PREVIEW_BYTES: usize = 16 * 1024;
+
+/// What a preview shows below its summary
+#[derive(Debug, Clone, PartialEq)]
+pub enum PreviewBody {
+    /// Sorted child names, directories with a trailing `/`, and how many there are in all
+    Children { names: Vec<String>, total: usize },
+    /// Leading lines of a text file
+    Text(Vec<String>),
+    /// A file with NUL bytes or invalid UTF-8
+    Binary,
+    /// Reading failed, e.g. permission denied
+    Error(String),
+}
+
+/// A preview of one path
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preview {
+    pub path: PathBuf,
+    pub meta: Option<EntryMeta>,
+    pub body: PreviewBody,
+}
+
+impl Preview {
+    /// Read up to `lines` children or lines of `path`
+    pub fn build(path: &Path, is_dir: bool, lines: usize) -> Self {
+        let body = if is_dir {
+            read_children(path, lines)
+        } else {
+            read_text(path, lines)
+        };
+        Self {
+            path: path.to_path_buf(),
+            meta: EntryMeta::read(path, is_dir),
+            body,
+        }
+    }
+}
+
+fn read_children(dir: &Path, limit: usize) -> PreviewBody {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => return PreviewBody::Error(e.to_string()),
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(Result::ok)
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.path().is_dir() {
+                format!("{}/", name)
+            } else {
+                name
+            }
+        })
+        .collect();
+    names.sort_by(|a, b| {
+        b.ends_with('/')
+            .cmp(&a.ends_with('/'))
+            .then_with(|| natural_cmp(a, b))
+    });
+    let total = names.len();
+    names.truncate(limit);
+    PreviewBody::Children { names, total }
+}
+
+fn read_text(path: &Path, limit: usize) -> PreviewBody {
+    let mut buf = Vec::with_capacity(PREVIEW_BYTES);
+    let read = std::fs::File::open(path)
+        .and_then(|file| file.take(PREVIEW_BYTES as u64).read_to_end(&mut buf));
+    if let Err(e) = read {
+        return PreviewBody::Error(e.to_string());
+    }
+    let text = match std::str::from_utf8(&buf) {
+        Ok(text) => text,
+        // The read may have stopped in the middle of a character
+        Err(e) if e.error_len().is_none() => {
+            std::str::from_utf8(&buf[..e.valid_up_to()]).unwrap_or("")
+        }
+        Err(_) => return PreviewBody::Binary,
+    };
+    if text.contains('\0') {
+        return PreviewBody::Binary;
+    }
+    let lines = text
+        .lines()
+        .take(limit)
+        .map(|line| {
+            line.replace('\t', "    ")
+                .chars()
+                .filter(|c| !c.is_control())
+                .collect()
+        })
+        .collect();
+    PreviewBody::Text(lines)
+}
+
+/// Builds previews on a worker thread; dropping it stops the thread
+#[derive(Debug)]
+pub struct Previewer {
+    requests: mpsc::Sender<(PathBuf, bool)>,
+    results: mpsc::Receiver<Preview>,
+    /// Path of the newest request
+    requested: Option<PathBuf>,
+    /// The newest request has not been handed out by `poll` or `wait` yet
+    pending: bool,
+}
+
+impl Previewer {
+    pub fn spawn(lines: usize) -> Self {
+        let (requests, pending) = mpsc::channel::<(PathBuf, bool)>();
+        let (done, results) = mpsc::channel();
+        thread::spawn(move || {
+            while let Ok(mut request) = pending.recv() {
+                // Selections that were scrolled past are not worth building
+                while let Ok(newer) = pending.try_recv() {
+                    request = newer;
+                }
+                let (path, is_dir) = request;
+                if done.send(Preview::build(&path, is_dir, lines)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests,
+            results,
+            requested: None,
+            pending: false,
+        }
+    }
+
+    /// Ask for a preview of `path` unless it is already the newest request
+    pub fn request(&mut self, path: &Path, is_dir: bool) {
+        if self.requested.as_deref() != Some(path) {
+            self.requested = Some(path.to_path_buf());
+            self.pending = true;
+            let _ = self.requests.send((path.to_path_buf(), is_dir));
+        }
+    }
+
+    /// Forget the newest request so the next `request` is sent again, e.g. after a reload
+    pub fn invalidate(&mut self) {
+        self.requested = None;
+    }
+
+    /// The preview for the newest request, if it is ready
+    pub fn poll(&mut self) -> Option<Preview> {
+        let preview = self
+            .results
+            .try_iter()
+            .filter(|preview| self.requested.as_ref() == Some(&preview.path))
+            .last()?;
+        self.pending = false;
+        Some(preview)
+    }
+
+    /// Block until the preview for the newest request is ready. Returns `None` at once
+    /// when that preview was already handed out, so the caller keeps the one it has.
+    pub fn wait(&mut self) -> Option<Preview> {
+        if !self.pending {
+            return None;
+        }
+        let preview = self
+            .results
+            .iter()
+            .find(|preview| self.requested.as_ref() == Some(&preview.path))?;
+        self.pending = false;
+        Some(preview)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_previews() {
+        let root = std::env::temp_dir().join("jump_test_preview");
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["src/v10", "src/v9"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join("src/main.rs"), "fn main() {\n\tprintln!();\n}\n").unwrap();
+        std::fs::write(root.join("image.png"), b"\x89PNG\r\n\x1a\n\0\0").unwrap();
+
+        let preview = Preview::build(&root.join("src"), true, 2);
+        let expected = PreviewBody::Children {
+            names: vec!["v9/".to_string(), "v10/".to_string()],
+            total: 3,
+        };
+        assert_eq!(preview.body, expected);
+        assert_eq!(preview.meta.unwrap().item_count, Some(3));
+
+        let preview = Preview::build(&root.join("src/main.rs"), false, 10);
+        let lines = ["fn main() {", "    println!();", "}"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(preview.body, PreviewBody::Text(lines));
+
+        let preview = Preview::build(&root.join("image.png"), false, 10);
+        assert_eq!(preview.body, PreviewBody::Binary);
+        assert!(matches!(
+            Preview::build(&root.join("gone"), false, 10).body,
+            PreviewBody::Error(_)
+        ));
+    }
+
+    #[test]
+    fn test_previewer_serves_newest_request() {
+        let root = std::env::temp_dir().join("jump_test_previewer");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("a")).unwrap();
+        std::fs::create_dir_all(root.join("b/inner")).unwrap();
+
+        let mut previewer = Previewer::spawn(PREVIEW_LINES);
+        assert_eq!(previewer.wait(), None);
+        previewer.request(&root.join("a"), true);
+        previewer.request(&root.join("b"), true);
+        let preview = previewer.wait().unwrap();
+        assert_eq!(preview.path, root.join("b"));
+        assert!(matches!(
+            preview.body,
+            PreviewBody::Children { total: 1, .. }
+        ));
+        assert_eq!(previewer.poll(), None);
+    }
+
+    #[test]
+    fn test_previewer_wait_twice_for_same_selection() {
+        let root = std::env::temp_dir().join("jump_test_previewer_twice");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("a")).unwrap();
+
+        let mut previewer = Previewer::spawn(PREVIEW_LINES);
+        previewer.request(&root.join("a"), true);
+        assert_eq!(previewer.wait().unwrap().path, root.join("a"));
+
+        // The same selection is not sent again, so there is nothing to wait for
+        previewer.request(&root.join("a"), true);
+        assert_eq!(previewer.wait(), None);
+
+        previewer.invalidate();
+        previewer.request(&root.join("a"), true);
+        assert_eq!(previewer.wait().unwrap().path, root.join("a"));
+    }
+}
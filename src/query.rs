@@ -0,0 +1,156 @@
+//! Non-interactive "best match" lookup
+//!
+//! Ranks bookmarks, remembered directories and the children of the current
+//! directory against a query and picks a single path, so the shell wrapper
+//! can `cd` without drawing the TUI.
+
+use crate::database::entry::DirEntry as DbDirEntry;
+use crate::fs::DirEntry;
+use crate::fuzzy::FuzzyMatchEngine;
+
+/// Weight of the (log-scaled) frecency score relative to the fuzzy match score
+const FRECENCY_WEIGHT: f64 = 10.0;
+
+/// A path that can be returned by a query
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub path: String,
+    pub name: String,
+    pub bookmark_key: Option<String>,
+    pub frecency: f64,
+}
+
+impl Candidate {
+    pub fn from_db(entry: &DbDirEntry, now: i64) -> Self {
+        Self {
+            path: entry.path.clone(),
+            name: entry.name.clone(),
+            bookmark_key: entry.bookmark_key.clone(),
+            frecency: entry.frecency(now),
+        }
+    }
+
+    pub fn from_fs(entry: &DirEntry) -> Self {
+        Self {
+            path: entry.path.to_string_lossy().into_owned(),
+            name: entry.name.clone(),
+            bookmark_key: None,
+            frecency: 0.0,
+        }
+    }
+
+    fn score(&self, matcher: &FuzzyMatchEngine, pattern: &str) -> Option<f64> {
+        // An exact bookmark alias always wins
+        if self.bookmark_key.as_deref() == Some(pattern) {
+            return Some(f64::MAX);
+        }
+
+        let name_score = matcher.get_score(pattern, &self.name);
+        let alias_score = self
+            .bookmark_key
+            .as_deref()
+            .and_then(|key| matcher.get_score(pattern, key));
+        let best = name_score.max(alias_score)?;
+
+        Some(best as f64 + FRECENCY_WEIGHT * self.frecency.ln_1p())
+    }
+}
+
+/// Rank candidates against `pattern`, best first. Duplicate paths are merged.
+pub fn rank(
+    matcher: &FuzzyMatchEngine,
+    pattern: &str,
+    candidates: Vec<Candidate>,
+) -> Vec<(Candidate, f64)> {
+    let mut merged: Vec<Candidate> = Vec::with_capacity(candidates.len());
+    for candidate in candidates {
+        if let Some(existing) = merged.iter_mut().find(|c| c.path == candidate.path) {
+            existing.frecency = existing.frecency.max(candidate.frecency);
+            if existing.bookmark_key.is_none() {
+                existing.bookmark_key = candidate.bookmark_key;
+            }
+        } else {
+            merged.push(candidate);
+        }
+    }
+
+    let mut ranked: Vec<(Candidate, f64)> = merged
+        .into_iter()
+        .filter_map(|c| c.score(matcher, pattern).map(|score| (c, score)))
+        .collect();
+
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.path.cmp(&b.0.path)));
+    ranked
+}
+
+/// Return the single best path for `pattern`, if anything matches
+pub fn best_match(
+    matcher: &FuzzyMatchEngine,
+    pattern: &str,
+    candidates: Vec<Candidate>,
+) -> Option<String> {
+    rank(matcher, pattern, candidates)
+        .into_iter()
+        .next()
+        .map(|(c, _)| c.path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(path: &str, key: Option<&str>, frecency: f64) -> Candidate {
+        Candidate {
+            path: path.to_string(),
+            name: path.rsplit('/').next().unwrap().to_string(),
+            bookmark_key: key.map(str::to_string),
+            frecency,
+        }
+    }
+
+    #[test]
+    fn test_best_match_prefers_frecent() {
+        let matcher = FuzzyMatchEngine::new();
+        let candidates = vec![
+            candidate("/a/project", None, 0.0),
+            candidate("/b/project", None, 40.0),
+        ];
+        assert_eq!(
+            best_match(&matcher, "proj", candidates).as_deref(),
+            Some("/b/project")
+        );
+    }
+
+    #[test]
+    fn test_best_match_exact_bookmark_key() {
+        let matcher = FuzzyMatchEngine::new();
+        let candidates = vec![
+            candidate("/home/w", None, 100.0),
+            candidate("/srv/work", Some("w"), 0.0),
+        ];
+        assert_eq!(
+            best_match(&matcher, "w", candidates).as_deref(),
+            Some("/srv/work")
+        );
+    }
+
+    #[test]
+    fn test_best_match_none() {
+        let matcher = FuzzyMatchEngine::new();
+        let candidates = vec![candidate("/a/docs", None, 0.0)];
+        assert!(best_match(&matcher, "zzz", candidates).is_none());
+    }
+
+    #[test]
+    fn test_rank_merges_duplicate_paths() {
+        let matcher = FuzzyMatchEngine::new();
+        let candidates = vec![
+            candidate("/a/project", None, 0.0),
+            candidate("/a/project", Some("p"), 3.0),
+        ];
+        let ranked = rank(&matcher, "proj", candidates);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].0.bookmark_key.as_deref(), Some("p"));
+        assert_eq!(ranked[0].0.frecency, 3.0);
+    }
+}
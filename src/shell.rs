@@ -29,16 +29,18 @@ impl Shell {
 }
 
 /// Generate bash shell integration script
-pub fn generate_bash_init() -> String {
+pub fn generate_bash_init(editor: &str) -> String {
     let script = r#"# jump shell integration
 # Add this to ~/.bashrc
 
-# Autojump-style function
+# Autojump-style function; files are opened in the configured editor
 jump() {
     local target
     target="$(command jump "$@")"
     if [[ -n "$target" && -d "$target" ]]; then
         cd "$target" || return 1
+    elif [[ -n "$target" && -f "$target" ]]; then
+        @EDITOR@ "$target"
     fi
 }
 
@@ -48,27 +50,29 @@ j() { jump "$@"; }
 # Fuzzy find mode (Ctrl+F)
 _bind_jump_fzf() {
     local target
-    target="$(command jump --fuzzy)"
+    target="$(command jump)"
     if [[ -n "$target" && -d "$target" ]]; then
         cd "$target" || return 1
     fi
 }
 bind -x '"\C-f": _bind_jump_fzf'
 "#;
-    script.to_string()
+    script.replace("@EDITOR@", editor)
 }
 
 /// Generate zsh shell integration script
-pub fn generate_zsh_init() -> String {
+pub fn generate_zsh_init(editor: &str) -> String {
     let script = r#"# jump shell integration
 # Add this to ~/.zshrc
 
-# Autojump-style function
+# Autojump-style function; files are opened in the configured editor
 jump() {
     local target
     target="$(command jump "$@")"
     if [[ -n "$target" && -d "$target" ]]; then
         cd "$target" || return 1
+    elif [[ -n "$target" && -f "$target" ]]; then
+        @EDITOR@ "$target"
     fi
 }
 
@@ -78,7 +82,7 @@ j() { jump "$@"; }
 # Fuzzy find mode (Ctrl+F)
 _bind_jump_fzf() {
     local target
-    target="$(command jump --fuzzy)"
+    target="$(command jump)"
     if [[ -n "$target" && -d "$target" ]]; then
         cd "$target" || return 1
     fi
@@ -86,19 +90,21 @@ _bind_jump_fzf() {
 zle -N _bind_jump_fzf
 bindkey '^F' _bind_jump_fzf
 "#;
-    script.to_string()
+    script.replace("@EDITOR@", editor)
 }
 
 /// Generate fish shell integration script
-pub fn generate_fish_init() -> String {
+pub fn generate_fish_init(editor: &str) -> String {
     let script = r#"# jump shell integration
 # Add this to ~/.config/fish/config.fish
 
-# Main jump function
+# Main jump function; files are opened in the configured editor
 function jump --description "Quick directory navigation"
     set -l target (command jump $argv)
     if test -n "$target" -a -d "$target"
         cd $target
+    else if test -n "$target" -a -f "$target"
+        @EDITOR@ $target
     end
 end
 
@@ -107,7 +113,7 @@ abbr -a j jump
 
 # Fuzzy find mode (Ctrl+F)
 function _jump_fish_fzf --description "Jump with fuzzy search"
-    set -l target (command jump --fuzzy)
+    set -l target (command jump)
     if test -n "$target" -a -d "$target"
         cd $target
     end
@@ -115,21 +121,21 @@ function _jump_fish_fzf --description "Jump with fuzzy search"
 end
 bind \cf _jump_fish_fzf
 "#;
-    script.to_string()
+    script.replace("@EDITOR@", editor)
 }
 
 /// Generate shell init script for a specific shell
-pub fn generate_shell_init(shell: Shell) -> String {
+pub fn generate_shell_init(shell: Shell, editor: &str) -> String {
     match shell {
-        Shell::Bash => generate_bash_init(),
-        Shell::Zsh => generate_zsh_init(),
-        Shell::Fish => generate_fish_init(),
+        Shell::Bash => generate_bash_init(editor),
+        Shell::Zsh => generate_zsh_init(editor),
+        Shell::Fish => generate_fish_init(editor),
     }
 }
 
 /// Output shell init script to stdout
-pub fn print_shell_init(shell: Shell) -> io::Result<()> {
-    let script = generate_shell_init(shell);
+pub fn print_shell_init(shell: Shell, editor: &str) -> io::Result<()> {
+    let script = generate_shell_init(shell, editor);
     println!("{}", script);
     Ok(())
 }
@@ -145,16 +151,29 @@ _{name}_jump() {{
     _init_completion || return
 
     case "$prev" in
-        --completions)
-            COMPREPLY=(bash zsh fish)
+        --completions|completions)
+            COMPREPLY=($(compgen -W "bash zsh fish" -- "$cur"))
             return
             ;;
-        --shell-init)
-            COMPREPLY=(bash zsh fish auto)
+        --shell-init|init)
+            COMPREPLY=($(compgen -W "bash zsh fish auto" -- "$cur"))
+            return
+            ;;
+        -b|--bookmark|bookmark)
+            COMPREPLY=($(compgen -W "add remove list jump export import" -- "$cur"))
+            return
+            ;;
+        --from)
+            COMPREPLY=($(compgen -W "zoxide autojump z fasd" -- "$cur"))
             return
             ;;
     esac
 
+    if [[ $cword -eq 1 && "$cur" != -* ]]; then
+        COMPREPLY=($(compgen -W "query bookmark init completions import prune config" -- "$cur"))
+        return
+    fi
+
     if [[ "$cur" == -* ]]; then
         COMPREPLY=($(compgen -W "$({name} --help 2>/dev/null | grep -oP '(--\S+)' | tr '\n' ' ')" -- "$cur"))
         return
@@ -177,9 +196,13 @@ _{name}() {{
         "-v[Show version]"
         "-a[Show hidden directories]"
         "--all[Show hidden directories]"
+        "--no-tui[Print the best match without opening the TUI]"
+        "--no-stale-check[Skip the missing bookmark check]"
+        "--depth[Start in deep search down to N levels]:depth:"
         "--bookmark[Bookmark management]"
         "--shell-init[Print shell initialization script]"
         "--completions[Print shell completion script]"
+        "1:command:(query bookmark init completions import prune config)"
     )
     _arguments -s $args
 }}
@@ -201,6 +224,9 @@ complete -c {name} -f -a "(
     echo --shell-init
     echo --completions
 )"
+complete -c {name} -f -n "__fish_use_subcommand" -a "query bookmark init completions import prune config"
+complete -c {name} -f -n "__fish_seen_subcommand_from bookmark" -a "add remove list jump export import"
+complete -c {name} -f -n "__fish_seen_subcommand_from init completions" -a "bash zsh fish"
 "#,
         name = name
     )
@@ -4,7 +4,7 @@ mod tests {
 
     #[test]
     fn test_bash_init_contains_function() {
-        let script = generate_bash_init();
+        let script = generate_bash_init("vim");
         assert!(script.contains("jump()"));
         assert!(script.contains("j()"));
         assert!(script.contains("command jump"));
@@ -13,7 +13,7 @@ mod tests {
 
     #[test]
     fn test_zsh_init_contains_function() {
-        let script = generate_zsh_init();
+        let script = generate_zsh_init("vim");
         assert!(script.contains("jump()"));
         assert!(script.contains("j()"));
         assert!(script.contains("command jump"));
@@ -22,7 +22,7 @@ mod tests {
 
     #[test]
     fn test_fish_init_contains_function() {
-        let script = generate_fish_init();
+        let script = generate_fish_init("vim");
         assert!(script.contains("function jump"));
         assert!(script.contains("command jump"));
         assert!(script.contains("abbr"));
@@ -31,57 +31,57 @@ mod tests {
 
     #[test]
     fn test_bash_init_has_cd() {
-        let script = generate_bash_init();
+        let script = generate_bash_init("vim");
         assert!(script.contains("cd \"$target\""));
     }
 
     #[test]
     fn test_zsh_init_has_cd() {
-        let script = generate_zsh_init();
+        let script = generate_zsh_init("vim");
         assert!(script.contains("cd \"$target\""));
     }
 
     #[test]
     fn test_fish_init_has_cd() {
-        let script = generate_fish_init();
+        let script = generate_fish_init("vim");
         assert!(script.contains("cd $target"));
     }
 
     #[test]
     fn test_bash_init_has_fuzzy_binding() {
-        let script = generate_bash_init();
+        let script = generate_bash_init("vim");
         assert!(script.contains("Ctrl+F") || script.contains("\\C-f"));
     }
 
     #[test]
     fn test_zsh_init_has_fuzzy_binding() {
-        let script = generate_zsh_init();
+        let script = generate_zsh_init("vim");
         assert!(script.contains("^F"));
     }
 
     #[test]
     fn test_fish_init_has_fuzzy_binding() {
-        let script = generate_fish_init();
+        let script = generate_fish_init("vim");
         assert!(script.contains("\\cf"));
     }
 
     #[test]
     fn test_bash_init_not_empty() {
-        let script = generate_bash_init();
+        let script = generate_bash_init("vim");
         assert!(!script.is_empty());
         assert!(script.len() > 100);
     }
 
     #[test]
     fn test_zsh_init_not_empty() {
-        let script = generate_zsh_init();
+        let script = generate_zsh_init("vim");
         assert!(!script.is_empty());
         assert!(script.len() > 100);
     }
 
     #[test]
     fn test_fish_init_not_empty() {
-        let script = generate_fish_init();
+        let script = generate_fish_init("vim");
         assert!(!script.is_empty());
         assert!(script.len() > 100);
     }
@@ -0,0 +1,215 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use crate::fs::DirEntry;
+
+/// Optional metadata column drawn before each name in the list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    Permissions,
+    Owner,
+    Size,
+    /// Entries in a directory
+    Items,
+    /// Time since the last modification
+    Modified,
+}
+
+impl Column {
+    /// Display order, the same as `ls -l`
+    pub const ALL: [Column; 5] = [
+        Column::Permissions,
+        Column::Owner,
+        Column::Size,
+        Column::Items,
+        Column::Modified,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::Permissions => "permissions",
+            Column::Owner => "owner",
+            Column::Size => "size",
+            Column::Items => "items",
+            Column::Modified => "modified",
+        }
+    }
+
+    fn width(self) -> usize {
+        match self {
+            Column::Permissions => 10,
+            Column::Owner => 8,
+            Column::Size | Column::Items => 5,
+            Column::Modified => 4,
+        }
+    }
+
+    /// The cell for `entry`, padded to the column width; blank if its metadata is unreadable
+    pub fn cell(self, entry: &DirEntry, now: SystemTime) -> String {
+        let text = entry.metadata().map(|meta| match self {
+            Column::Permissions => meta
+                .mode
+                .map(|mode| permissions(mode, entry.link.is_some()))
+                .unwrap_or_default(),
+            Column::Owner => meta.owner.clone().unwrap_or_default(),
+            Column::Size if entry.is_dir => "-".to_string(),
+            Column::Size => human_size(meta.size),
+            Column::Items => meta
+                .item_count
+                .map_or_else(|| "-".to_string(), |count| count.to_string()),
+            Column::Modified => meta
+                .modified
+                .map(|modified| age(now.duration_since(modified).unwrap_or_default()))
+                .unwrap_or_default(),
+        });
+        let text: String = text
+            .unwrap_or_default()
+            .chars()
+            .take(self.width())
+            .collect();
+        match self {
+            Column::Permissions | Column::Owner => format!("{:<1$}", text, self.width()),
+            _ => format!("{:>1$}", text, self.width()),
+        }
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Column {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Column::ALL
+            .into_iter()
+            .find(|column| column.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Column::ALL.iter().map(|c| c.name()).collect();
+                format!("unknown column '{}' (expected {})", s, names.join(", "))
+            })
+    }
+}
+
+/// `drwxr-xr-x` from `st_mode`; links show `l` whatever they point to
+fn permissions(mode: u32, is_link: bool) -> String {
+    let kind = match mode & 0o170000 {
+        _ if is_link => 'l',
+        0o040000 => 'd',
+        _ => '-',
+    };
+    let bits = (0..9).rev().map(|bit| match (mode >> bit & 1, bit % 3) {
+        (0, _) => '-',
+        (_, 2) => 'r',
+        (_, 1) => 'w',
+        _ => 'x',
+    });
+    std::iter::once(kind).chain(bits).collect()
+}
+
+/// `ls -h` style size: `512`, `4.0K`, `12M`
+pub(super) fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["K", "M", "G", "T", "P"];
+    if bytes < 1024 {
+        return bytes.to_string();
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if size < 10.0 {
+        format!("{:.1}{}", size, UNITS[unit])
+    } else {
+        format!("{:.0}{}", size, UNITS[unit])
+    }
+}
+
+/// Largest whole unit of `elapsed`: `now`, `42m`, `5h`, `12d`, `3mo`, `2y`
+pub(super) fn age(elapsed: Duration) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+    let secs = elapsed.as_secs();
+    match secs {
+        s if s < MINUTE => "now".to_string(),
+        s if s < HOUR => format!("{}m", s / MINUTE),
+        s if s < DAY => format!("{}h", s / HOUR),
+        s if s < 30 * DAY => format!("{}d", s / DAY),
+        s if s < 365 * DAY => format!("{}mo", s / (30 * DAY)),
+        s => format!("{}y", s / (365 * DAY)),
+    }
+}
+
+/// Modified within the last day, highlighted so recent work stands out
+pub fn is_recent(entry: &DirEntry, now: SystemTime) -> bool {
+    entry
+        .metadata()
+        .and_then(|meta| meta.modified)
+        .and_then(|modified| now.duration_since(modified).ok())
+        .is_some_and(|elapsed| elapsed < Duration::from_secs(24 * 60 * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formatting() {
+        assert_eq!(permissions(0o040755, false), "drwxr-xr-x");
+        assert_eq!(permissions(0o100640, false), "-rw-r-----");
+        assert_eq!(permissions(0o040700, true), "lrwx------");
+
+        assert_eq!(human_size(512), "512");
+        assert_eq!(human_size(4096), "4.0K");
+        assert_eq!(human_size(12 * 1024 * 1024 + 1), "12M");
+
+        assert_eq!(age(Duration::from_secs(30)), "now");
+        assert_eq!(age(Duration::from_secs(42 * 60)), "42m");
+        assert_eq!(age(Duration::from_secs(5 * 3600 + 59)), "5h");
+        assert_eq!(age(Duration::from_secs(400 * 86400)), "1y");
+
+        assert_eq!("size".parse::<Column>(), Ok(Column::Size));
+        assert!("color".parse::<Column>().unwrap_err().contains("modified"));
+    }
+
+    #[test]
+    fn test_cells_pad_and_load_lazily() {
+        let root = std::env::temp_dir().join("jump_test_columns");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("src/ui")).unwrap();
+        std::fs::write(root.join("src/main.rs"), vec![b'x'; 2048]).unwrap();
+
+        let entry = |name: &str, is_dir| DirEntry {
+            path: root.join(name),
+            name: name.to_string(),
+            is_dir,
+            link: None,
+            meta: Default::default(),
+        };
+        let now = SystemTime::now();
+        let dir = entry("src", true);
+        assert!(dir.meta.get().is_none());
+        assert_eq!(Column::Items.cell(&dir, now), "    2");
+        assert!(dir.meta.get().is_some());
+        // Clones share the loaded metadata
+        assert!(dir.clone().meta.get().is_some());
+        assert_eq!(Column::Size.cell(&dir, now), "    -");
+        assert_eq!(Column::Modified.cell(&dir, now), " now");
+        assert!(is_recent(&dir, now));
+
+        let file = entry("src/main.rs", false);
+        assert_eq!(Column::Size.cell(&file, now), " 2.0K");
+        assert_eq!(Column::Items.cell(&file, now), "    -");
+        #[cfg(unix)]
+        assert!(Column::Permissions.cell(&file, now).starts_with("-rw"));
+
+        let missing = entry("gone", true);
+        assert_eq!(Column::Owner.cell(&missing, now), " ".repeat(8));
+    }
+}
@@ -1,17 +1,27 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph},
     Frame,
 };
 
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{Duration, Instant, SystemTime};
 
+use super::columns::{self, Column};
+use super::{merge_sorted, SortMode, SortOrder, TextInput, Theme};
+use crate::config::settings::Settings;
 use crate::database::entry::DirEntry as DbDirEntry;
-use crate::fs::{self, DirEntry};
-use crate::fuzzy::FuzzyMatchEngine;
-use crate::Mode;
+use crate::fs::{self, DirEntry, DirWatcher, Symlink, WalkOptions};
+use crate::fuzzy::{FuzzyMatchEngine, Query};
+use crate::preview::{Preview, PreviewBody, Previewer, PREVIEW_LINES};
+use crate::app::Mode;
+
+/// Frames of the spinner shown while a directory is being scanned
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 
 /// Draw the fuzzy search TUI
 pub fn draw_fuzzy(frame: &mut Frame, state: &FuzzyState, mode: &Mode) {
@@ -20,6 +30,8 @@ pub fn draw_fuzzy(frame: &mut Frame, state: &FuzzyState, mode: &Mode) {
         return draw_help(frame);
     }
 
+    let theme = state.theme;
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
@@ -30,62 +42,75 @@ pub fn draw_fuzzy(frame: &mut Frame, state: &FuzzyState, mode: &Mode) {
             Constraint::Min(0),
         ])
         .split(frame.area());
+    // The list keeps the left of the last row, the preview takes the right
+    let (list_area, preview_area) = if state.show_preview {
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(100 - state.preview_width),
+                Constraint::Percentage(state.preview_width),
+            ])
+            .split(chunks[3]);
+        (panes[0], Some(panes[1]))
+    } else {
+        (chunks[3], None)
+    };
 
     // Draw help bar
     let help_spans = vec![
         Span::styled(
             "j/k",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ),
         Span::styled(" [up/down]  ", Style::default().fg(Color::DarkGray)),
         Span::styled(
             "h/l",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ),
         Span::styled(" [in/out]  ", Style::default().fg(Color::DarkGray)),
         Span::styled(
             "/",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ),
         Span::styled(" [search]  ", Style::default().fg(Color::DarkGray)),
         Span::styled(
             "a",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ),
         Span::styled(" [create]  ", Style::default().fg(Color::DarkGray)),
         Span::styled(
             ".",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ),
         Span::styled(" [hidden]  ", Style::default().fg(Color::DarkGray)),
         Span::styled(
             "f",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ),
         Span::styled(" [files]  ", Style::default().fg(Color::DarkGray)),
         Span::styled(
             "Enter",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ),
         Span::styled(" [select]  ", Style::default().fg(Color::DarkGray)),
         Span::styled(
             "?",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ),
         Span::styled(" [help]", Style::default().fg(Color::DarkGray)),
@@ -96,13 +121,36 @@ pub fn draw_fuzzy(frame: &mut Frame, state: &FuzzyState, mode: &Mode) {
     // Draw toggle status line
     let hidden_text = if state.show_hidden { "[hidden:ON]" } else { "[hidden:off]" };
     let files_text = if state.show_files { "[files:ON]" } else { "[files:off]" };
+    let ignored_text = if state.show_ignored { "[ignored:ON]" } else { "[ignored:off]" };
+    let sort_text = format!(
+        "[sort:{}{}]",
+        state.sort,
+        if state.sort_reverse { " rev" } else { "" }
+    );
+    let deep_text = if state.deep_search {
+        format!("[deep:ON depth {}]", state.search_depth)
+    } else {
+        "[deep:off]".to_string()
+    };
 
     let toggle_spans = vec![
-        Span::styled(hidden_text, Style::default().fg(Color::Cyan)),
+        Span::styled(hidden_text, Style::default().fg(theme.accent)),
         Span::styled("  ", Style::default()),
-        Span::styled(files_text, Style::default().fg(Color::Cyan)),
+        Span::styled(files_text, Style::default().fg(theme.accent)),
+        Span::styled("  ", Style::default()),
+        Span::styled(ignored_text, Style::default().fg(theme.accent)),
+        Span::styled("  ", Style::default()),
+        Span::styled(deep_text, Style::default().fg(theme.accent)),
+        Span::styled("  ", Style::default()),
+        Span::styled(sort_text, Style::default().fg(theme.accent)),
     ];
-    let toggle_line = Paragraph::new(Line::from(toggle_spans));
+    let toggle_line = match &state.status {
+        Some(status) => Paragraph::new(Span::styled(
+            status.as_str(),
+            Style::default().fg(Color::Red),
+        )),
+        None => Paragraph::new(Line::from(toggle_spans)),
+    };
     frame.render_widget(toggle_line, chunks[1]);
 
     // Draw search/bookmark input
@@ -112,17 +160,41 @@ pub fn draw_fuzzy(frame: &mut Frame, state: &FuzzyState, mode: &Mode) {
                 .selected_item()
                 .map(|item| item.entry.name.as_str())
                 .unwrap_or("");
-            let display = format!(" Bookmark '{}' as: {} ", selected_name, alias);
-            let input_block = Paragraph::new(display)
-                .style(Style::default().fg(Color::Blue))
+            let mut spans = vec![Span::raw(format!(" Bookmark '{}' as: ", selected_name))];
+            spans.extend(input_spans(alias));
+            let input_block = Paragraph::new(Line::from(spans))
+                .style(Style::default().fg(theme.selection))
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
-                        .border_style(Style::default().fg(Color::Blue))
+                        .border_style(Style::default().fg(theme.selection))
                         .title(" BOOKMARK "),
                 );
             frame.render_widget(input_block, chunks[2]);
         }
+        Mode::BookmarkOverwrite {
+            alias,
+            existing_path,
+        } => {
+            let missing = if state.stale_paths.contains(existing_path) {
+                " (missing)"
+            } else {
+                ""
+            };
+            let display = format!(
+                " '{}' already points to {}{}. Press Enter to overwrite, Esc to cancel ",
+                alias, existing_path, missing
+            );
+            let input_block = Paragraph::new(display)
+                .style(Style::default().fg(Color::Yellow))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(Color::Yellow))
+                        .title(" OVERWRITE BOOKMARK "),
+                );
+            frame.render_widget(input_block, chunks[2]);
+        }
         Mode::BookmarkRemove => {
             let selected_name = state
                 .selected_item()
@@ -146,9 +218,46 @@ pub fn draw_fuzzy(frame: &mut Frame, state: &FuzzyState, mode: &Mode) {
                 );
             frame.render_widget(input_block, chunks[2]);
         }
-        Mode::CreateEntry(name) => {
-            let display = format!(" New entry (end with / for dir): {}█ ", name);
+        Mode::ForgetEntry => {
+            let path = state
+                .selected_item()
+                .map(|item| item.path())
+                .unwrap_or_default();
+            let display = format!(
+                " Forget missing {} and its history? Press Enter to confirm ",
+                path
+            );
             let input_block = Paragraph::new(display)
+                .style(Style::default().fg(Color::Red))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(Color::Red))
+                        .title(" FORGET ENTRY "),
+                );
+            frame.render_widget(input_block, chunks[2]);
+        }
+        Mode::BookmarkRepoint(target) => {
+            let bookmark_key = state
+                .selected_item()
+                .and_then(|item| item.bookmark_key.as_deref())
+                .unwrap_or("");
+            let mut spans = vec![Span::raw(format!(" Repoint '{}' to: ", bookmark_key))];
+            spans.extend(input_spans(target));
+            let input_block = Paragraph::new(Line::from(spans))
+                .style(Style::default().fg(Color::Yellow))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(Color::Yellow))
+                        .title(" REPOINT BOOKMARK "),
+                );
+            frame.render_widget(input_block, chunks[2]);
+        }
+        Mode::CreateEntry(name) => {
+            let mut spans = vec![Span::raw(" New entry (end with / for dir): ")];
+            spans.extend(input_spans(name));
+            let input_block = Paragraph::new(Line::from(spans))
                 .style(Style::default().fg(Color::Green))
                 .block(
                     Block::default()
@@ -159,11 +268,17 @@ pub fn draw_fuzzy(frame: &mut Frame, state: &FuzzyState, mode: &Mode) {
             frame.render_widget(input_block, chunks[2]);
         }
         _ => {
-            let search_display = format!(" Search: {} ", state.search_query);
+            let search_display = if *mode == Mode::Search {
+                let mut spans = vec![Span::raw(" Search: ")];
+                spans.extend(input_spans(&state.search_query));
+                Line::from(spans)
+            } else {
+                Line::from(format!(" Search: {} ", state.search_query))
+            };
             let (search_style, border_style, title) = if *mode == Mode::Search {
                 (
                     Style::default().fg(Color::Yellow),
-                    Style::default().fg(Color::Blue),
+                    Style::default().fg(theme.selection),
                     format!(" SEARCH: {} ", state.current_dir.display()),
                 )
             } else if state.search_query.is_empty() {
@@ -186,16 +301,31 @@ pub fn draw_fuzzy(frame: &mut Frame, state: &FuzzyState, mode: &Mode) {
                     .border_style(border_style)
                     .title(title),
             );
-            frame.render_widget(search_block, chunks[1]);
+            frame.render_widget(search_block, chunks[2]);
         }
     }
 
     // Draw results
     let result_count = state.result_count();
-    let title = if state.search_query.is_empty() {
-        format!(" all directories ({}) ", result_count)
+    let label = if !state.search_query.is_empty() {
+        "results"
+    } else if state.deep_search {
+        "deep search"
     } else {
-        format!(" results ({}) ", result_count)
+        "all directories"
+    };
+    let title = match state.scan_elapsed() {
+        Some(elapsed) => {
+            let frame = (elapsed.as_millis() / 100) as usize % SPINNER.len();
+            format!(
+                " {} ({}) {} scanning {} ",
+                label,
+                result_count,
+                SPINNER[frame],
+                state.all_items.len()
+            )
+        }
+        None => format!(" {} ({}) ", label, result_count),
     };
 
     let items: Vec<ListItem> = if state.items.is_empty() {
@@ -204,19 +334,18 @@ pub fn draw_fuzzy(frame: &mut Frame, state: &FuzzyState, mode: &Mode) {
             Style::default().fg(Color::DarkGray),
         )]))]
     } else {
+        // Only visible rows are drawn, so only their metadata is read
+        let now = SystemTime::now();
         state
             .items
             .iter()
             .skip(state.scroll_offset)
+            .take(list_area.height as usize)
             .enumerate()
             .map(|(idx, item)| {
                 let global_idx = state.scroll_offset + idx;
                 let is_selected = global_idx == state.selected_index;
-                let rel_num = if global_idx > state.selected_index {
-                    global_idx - state.selected_index
-                } else {
-                    state.selected_index - global_idx
-                };
+                let rel_num = global_idx.abs_diff(state.selected_index);
                 let rel_num_str = if rel_num == 0 {
                     "  0 ".to_string()
                 } else {
@@ -232,15 +361,16 @@ pub fn draw_fuzzy(frame: &mut Frame, state: &FuzzyState, mode: &Mode) {
                     Style::default().fg(Color::Gray)
                 };
                 let num_span = Span::styled(rel_num_str, num_style);
+                let matched = |text, indices, style| highlight(text, indices, style, theme.accent);
 
-                if item.is_bookmark {
+                let mut spans = if item.is_bookmark {
                     let alias = item.bookmark_key.as_deref().unwrap_or("");
                     let (prefix, star_style, alias_style, arrow_style, name_style) = if is_selected
                     {
                         let selection_color = if *mode == Mode::Search {
                             Color::DarkGray
                         } else {
-                            Color::Blue
+                            theme.selection
                         };
                         (
                             Span::styled(
@@ -250,10 +380,10 @@ pub fn draw_fuzzy(frame: &mut Frame, state: &FuzzyState, mode: &Mode) {
                                     .add_modifier(Modifier::BOLD),
                             ),
                             Style::default()
-                                .fg(Color::Yellow)
+                                .fg(theme.bookmark)
                                 .add_modifier(Modifier::BOLD),
                             Style::default()
-                                .fg(Color::Yellow)
+                                .fg(theme.bookmark)
                                 .add_modifier(Modifier::BOLD),
                             Style::default().fg(selection_color),
                             Style::default()
@@ -263,28 +393,27 @@ pub fn draw_fuzzy(frame: &mut Frame, state: &FuzzyState, mode: &Mode) {
                     } else {
                         (
                             Span::raw(" "),
-                            Style::default().fg(Color::Yellow),
-                            Style::default().fg(Color::Yellow),
+                            Style::default().fg(theme.bookmark),
+                            Style::default().fg(theme.bookmark),
                             Style::default().fg(Color::DarkGray),
                             Style::default().fg(Color::White),
                         )
                     };
 
-                    let line = Line::from(vec![
-                        num_span,
-                        prefix,
-                        Span::styled(" ★ ", star_style),
-                        Span::styled(alias, alias_style),
-                        Span::styled(" → ", arrow_style),
-                        Span::styled(&item.entry.name, name_style),
-                    ]);
-                    ListItem::new(line)
+                    let mut spans = vec![num_span, prefix, Span::styled(" ★ ", star_style)];
+                    spans.extend(matched(alias, &item.key_indices, alias_style));
+                    spans.push(Span::styled(" → ", arrow_style));
+                    spans.extend(matched(&item.entry.name, &item.match_indices, name_style));
+                    if item.is_stale {
+                        spans = dim_stale(spans);
+                    }
+                    spans
                 } else if item.is_dir {
                     let (prefix, name_style, slash_style) = if is_selected {
                         let selection_color = if *mode == Mode::Search {
                             Color::DarkGray
                         } else {
-                            Color::Blue
+                            theme.selection
                         };
                         (
                             Span::styled(
@@ -306,27 +435,25 @@ pub fn draw_fuzzy(frame: &mut Frame, state: &FuzzyState, mode: &Mode) {
                         )
                     };
 
-                    let line = Line::from(vec![
-                        num_span,
-                        prefix,
-                        Span::styled(" ", name_style),
-                        Span::styled(&item.entry.name, name_style),
-                        Span::styled("/", slash_style),
-                    ]);
-                    ListItem::new(line)
+                    let mut spans = vec![num_span, prefix, Span::styled(" ", name_style)];
+                    spans.extend(matched(&item.entry.name, &item.match_indices, name_style));
+                    spans.push(Span::styled("/", slash_style));
+                    if let Some(link) = &item.entry.link {
+                        spans.extend(link_spans(link));
+                    }
+                    if item.is_stale {
+                        spans = dim_stale(spans);
+                    }
+                    spans
                 } else {
                     // File rendering
                     let (prefix, name_style) = if is_selected {
                         (
                             Span::styled(
                                 ">",
-                                Style::default()
-                                    .fg(Color::Yellow)
-                                    .add_modifier(Modifier::BOLD),
+                                Style::default().fg(theme.file).add_modifier(Modifier::BOLD),
                             ),
-                            Style::default()
-                                .fg(Color::Yellow)
-                                .add_modifier(Modifier::BOLD),
+                            Style::default().fg(theme.file).add_modifier(Modifier::BOLD),
                         )
                     } else {
                         (
@@ -335,21 +462,182 @@ pub fn draw_fuzzy(frame: &mut Frame, state: &FuzzyState, mode: &Mode) {
                         )
                     };
 
-                    let line = Line::from(vec![
-                        num_span,
-                        prefix,
-                        Span::raw(" "),
-                        Span::styled(&item.entry.name, name_style),
-                    ]);
-                    ListItem::new(line)
-                }
+                    let mut spans = vec![num_span, prefix, Span::raw(" ")];
+                    spans.extend(matched(&item.entry.name, &item.match_indices, name_style));
+                    if let Some(link) = &item.entry.link {
+                        spans.extend(link_spans(link));
+                    }
+                    spans
+                };
+                spans.splice(1..1, column_spans(&item.entry, &state.columns, now));
+                ListItem::new(Line::from(spans))
             })
             .collect()
     };
 
     let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
 
-    frame.render_widget(list, chunks[3]);
+    frame.render_widget(list, list_area);
+
+    if let Some(area) = preview_area {
+        draw_preview(frame, state, area);
+    }
+}
+
+/// Draw the selected entry's summary and contents, or a placeholder while it is built
+fn draw_preview(frame: &mut Frame, state: &FuzzyState, area: Rect) {
+    let dim = Style::default().fg(Color::DarkGray);
+    let title = state
+        .selected_item()
+        .map(|item| format!(" {} ", item.entry.name))
+        .unwrap_or_default();
+    let lines = match &state.preview {
+        _ if state.selected_item().is_none() => Vec::new(),
+        Some(preview) if state.preview_is_current() => {
+            let mut lines = vec![Line::from(Span::styled(preview_summary(preview), dim))];
+            match &preview.body {
+                PreviewBody::Children { names, total } => {
+                    lines.extend(names.iter().map(|name| {
+                        let style = if name.ends_with('/') {
+                            Style::default().fg(state.theme.accent)
+                        } else {
+                            Style::default()
+                        };
+                        Line::from(Span::styled(name.as_str(), style))
+                    }));
+                    if *total == 0 {
+                        lines.push(Line::from(Span::styled("(empty)", dim)));
+                    } else if *total > names.len() {
+                        let more = format!("… {} more", total - names.len());
+                        lines.push(Line::from(Span::styled(more, dim)));
+                    }
+                }
+                PreviewBody::Text(text) => {
+                    lines.extend(text.iter().map(|l| Line::from(l.as_str())))
+                }
+                PreviewBody::Binary => lines.push(Line::from(Span::styled("(binary file)", dim))),
+                PreviewBody::Error(e) => lines.push(Line::from(Span::styled(
+                    e.as_str(),
+                    Style::default().fg(Color::Red),
+                ))),
+            }
+            lines
+        }
+        _ => vec![Line::from(Span::styled("loading…", dim))],
+    };
+    let pane = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+    frame.render_widget(pane, area);
+}
+
+/// `12 items · 3h ago` for directories, `4.9K · 3h ago` for files
+fn preview_summary(preview: &Preview) -> String {
+    let Some(meta) = &preview.meta else {
+        return String::new();
+    };
+    let size = match meta.item_count {
+        Some(1) => "1 item".to_string(),
+        Some(count) => format!("{} items", count),
+        None => columns::human_size(meta.size),
+    };
+    match meta.modified {
+        Some(modified) => {
+            let elapsed = SystemTime::now()
+                .duration_since(modified)
+                .unwrap_or_default();
+            match columns::age(elapsed) {
+                now if now == "now" => format!("{} · modified just now", size),
+                age => format!("{} · modified {} ago", size, age),
+            }
+        }
+        None => size,
+    }
+}
+
+/// Text of an input field with a block cursor
+fn input_spans(input: &TextInput) -> Vec<Span<'_>> {
+    let (before, after) = input.split();
+    let mut rest = after.chars();
+    let under_cursor = rest
+        .next()
+        .map(String::from)
+        .unwrap_or_else(|| " ".to_string());
+    vec![
+        Span::raw(before),
+        Span::styled(
+            under_cursor,
+            Style::default().add_modifier(Modifier::REVERSED),
+        ),
+        Span::raw(rest.as_str()),
+    ]
+}
+
+/// Split `text` into spans, drawing the chars at `indices` (matched by the query) in
+/// `color`, bold and underlined
+fn highlight<'a>(text: &'a str, indices: &[usize], style: Style, color: Color) -> Vec<Span<'a>> {
+    if indices.is_empty() {
+        return vec![Span::styled(text, style)];
+    }
+    let matched_style = style
+        .fg(color)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut in_match = false;
+    for (pos, (offset, _)) in text.char_indices().enumerate() {
+        let is_match = indices.contains(&pos);
+        if is_match != in_match && offset > start {
+            let run_style = if in_match { matched_style } else { style };
+            spans.push(Span::styled(&text[start..offset], run_style));
+            start = offset;
+        }
+        in_match = is_match;
+    }
+    let run_style = if in_match { matched_style } else { style };
+    spans.push(Span::styled(&text[start..], run_style));
+    spans
+}
+
+/// Dim a row whose stored path no longer exists and append a marker
+fn dim_stale(spans: Vec<Span>) -> Vec<Span> {
+    let dim = Style::default()
+        .fg(Color::DarkGray)
+        .add_modifier(Modifier::DIM);
+    let mut spans: Vec<Span> = spans
+        .into_iter()
+        .map(|span| Span::styled(span.content, dim))
+        .collect();
+    spans.push(Span::styled(" ✗ missing", Style::default().fg(Color::Red)));
+    spans
+}
+
+/// Metadata cells drawn between the line number and the name
+fn column_spans(entry: &DirEntry, shown: &[Column], now: SystemTime) -> Vec<Span<'static>> {
+    shown
+        .iter()
+        .map(|column| {
+            let style = match column {
+                Column::Modified if columns::is_recent(entry, now) => {
+                    Style::default().fg(Color::Green)
+                }
+                _ => Style::default().fg(Color::DarkGray),
+            };
+            Span::styled(format!("{} ", column.cell(entry, now)), style)
+        })
+        .collect()
+}
+
+/// Marker and target shown after a symlink's name, flagging loops and dangling links
+fn link_spans(link: &Symlink) -> Vec<Span<'static>> {
+    let mut spans = vec![Span::styled(
+        format!(" ⇢ {}", link.target.display()),
+        Style::default().fg(Color::DarkGray),
+    )];
+    if link.is_loop {
+        spans.push(Span::styled(" ↻ loop", Style::default().fg(Color::Yellow)));
+    } else if link.is_broken() {
+        spans.push(Span::styled(" ✗ broken", Style::default().fg(Color::Red)));
+    }
+    spans
 }
 
 /// Draw the help screen
@@ -359,28 +647,37 @@ fn draw_help(frame: &mut Frame) {
         Line::from(vec![
             Span::styled("NAVIGATION", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
         ]),
-        Line::from("  j/k      Move selection up/down"),
-        Line::from("  h/l      Navigate out/in directories"),
-        Line::from("  g/G      Go to first/last item"),
-        Line::from("  Ctrl+U/D Page up/down"),
+        Line::from("  j/k      Move selection up/down (also arrows, Tab/Shift+Tab)"),
+        Line::from("  h/l      Navigate out/in directories (also Left/Right)"),
+        Line::from("  g/G      Go to first/last item (also Home/End)"),
+        Line::from("  Ctrl+U/D Page up/down (also PageUp/PageDown)"),
         Line::from(""),
         Line::from(vec![
             Span::styled("SEARCH & SELECTION", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
         ]),
         Line::from("  /        Start search"),
-        Line::from("  Enter    Select item (cd or open in vim)"),
+        Line::from("           'exact ^prefix suffix$ !exclude, a | b for either"),
+        Line::from("  Enter    Select item (cd or open in editor)"),
+        Line::from("  Left/Right/Home/End, Delete, Ctrl+W, Ctrl+U  Edit text inputs"),
         Line::from(""),
         Line::from(vec![
             Span::styled("BOOKMARKS", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
         ]),
         Line::from("  b        Bookmark selected directory"),
-        Line::from("  x        Remove bookmark"),
+        Line::from("  x        Remove bookmark, or forget a missing entry (Ctrl+X in search)"),
+        Line::from("  R        Repoint a missing bookmark (Ctrl+R in search)"),
         Line::from(""),
         Line::from(vec![
             Span::styled("TOGGLES & CREATION", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
         ]),
         Line::from("  .        Toggle hidden files"),
         Line::from("  f        Toggle file visibility"),
+        Line::from("  i        Toggle gitignored entries"),
+        Line::from("  r        Toggle deep search (match paths in subdirectories)"),
+        Line::from("  c p/o/s  Toggle permissions/owner/size column"),
+        Line::from("  c n/m    Toggle item count/modified column"),
+        Line::from("  s / S    Cycle sort order / reverse it"),
+        Line::from("  p        Toggle preview pane"),
         Line::from("  a        Create new file/directory (end with /)"),
         Line::from(""),
         Line::from(vec![
@@ -404,9 +701,17 @@ fn draw_help(frame: &mut Frame) {
 pub struct FuzzyItem {
     pub entry: DirEntry,
     pub match_score: i64,
+    /// Char positions in `entry.name` matched by the query, highlighted in the list
+    pub match_indices: Vec<usize>,
     pub is_bookmark: bool,
     pub bookmark_key: Option<String>,
+    /// Char positions in `bookmark_key` matched by the query
+    pub key_indices: Vec<usize>,
     pub is_dir: bool,
+    /// Stored path no longer exists (bookmarks and remembered directories only)
+    pub is_stale: bool,
+    /// A bookmark or remembered directory listed on its own, not found by the walk
+    pub standalone: bool,
 }
 
 impl FuzzyItem {
@@ -415,9 +720,13 @@ impl FuzzyItem {
         Self {
             entry,
             match_score,
+            match_indices: Vec::new(),
             is_bookmark: false,
             bookmark_key: None,
+            key_indices: Vec::new(),
             is_dir,
+            is_stale: false,
+            standalone: false,
         }
     }
 
@@ -427,23 +736,44 @@ impl FuzzyItem {
                 path: PathBuf::from(&db_entry.path),
                 name: db_entry.name.clone(),
                 is_dir: true,
+                link: None,
+                meta: Default::default(),
             },
             match_score: 0,
+            match_indices: Vec::new(),
             is_bookmark: true,
             bookmark_key: db_entry.bookmark_key.clone(),
+            key_indices: Vec::new(),
             is_dir: true,
+            is_stale: false,
+            standalone: true,
         }
     }
 
+    pub fn from_visited(db_entry: &DbDirEntry) -> Self {
+        let mut item = FuzzyItem::new(
+            DirEntry {
+                path: PathBuf::from(&db_entry.path),
+                name: db_entry.name.clone(),
+                is_dir: true,
+                link: None,
+                meta: Default::default(),
+            },
+            0,
+        );
+        item.standalone = true;
+        item
+    }
+
     pub fn path(&self) -> String {
         self.entry.path.to_string_lossy().into_owned()
     }
 }
 
 /// State for the fuzzy search TUI
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct FuzzyState {
-    pub search_query: String,
+    pub search_query: TextInput,
     pub all_items: Vec<FuzzyItem>,
     pub items: Vec<FuzzyItem>,
     pub selected_index: usize,
@@ -452,29 +782,110 @@ pub struct FuzzyState {
     pub current_dir: PathBuf,
     pub show_hidden: bool,
     pub show_files: bool,
+    /// List entries matched by `.gitignore`, `.ignore` and `.jumpignore`
+    pub show_ignored: bool,
     pub bookmarks: Vec<DbDirEntry>,
+    pub frecent: Vec<DbDirEntry>,
+    /// Positions of `frecent` entries in the frecency ranking, for `SortMode::Frecency`
+    ranks: HashMap<PathBuf, usize>,
+    pub stale_paths: Vec<String>,
     pub motion_count: Option<usize>,
+    /// Rows moved by page up/down
+    pub page_size: usize,
+    pub dirs_first: bool,
+    pub sort: SortMode,
+    pub sort_reverse: bool,
+    /// Select paths with symlinks resolved, see `selection_path`
+    pub resolve_symlinks: bool,
+    /// Metadata columns drawn before each name, in `Column::ALL` order
+    pub columns: Vec<Column>,
+    /// Show a preview of the selected entry beside the list
+    pub show_preview: bool,
+    /// Width of the preview pane in percent
+    pub preview_width: u16,
+    /// Latest preview; may belong to an entry selected earlier until the next arrives
+    pub preview: Option<Preview>,
+    pub theme: Theme,
+    /// Error from the last action, shown in place of the toggles until the next key
+    pub status: Option<String>,
+    /// List the subtree down to `search_depth` with paths relative to `current_dir`
+    pub deep_search: bool,
+    pub search_depth: usize,
+    /// Names skipped in every scan, see `WalkOptions::exclude`
+    pub exclude: Vec<String>,
+    /// Directory walk still sending entries for `current_dir`
+    scan: Option<Scan>,
+    /// Entry to select again, with its scroll offset, once a reload's scan finds it
+    reselect: Option<(PathBuf, usize)>,
+    /// Keep showing the previous entries until a reload's scan delivers
+    replace_items: bool,
+    /// The listing was walked with `WalkOptions::read_metadata`
+    has_metadata: bool,
+    /// Notices entries created, removed or renamed by other programs, in `current_dir`
+    /// or, during deep search, in the listed directories down to `search_depth`
+    watcher: Option<DirWatcher>,
+    /// First change the watcher reported since the last reload
+    changed_at: Option<Instant>,
+    /// Builds previews off the UI thread; started when the pane is first shown
+    previewer: Option<Previewer>,
 }
 
-impl FuzzyState {
-    /// Create a new FuzzyState by scanning the given directory
-    pub fn new_in_dir(dir: &Path, show_hidden: bool) -> Self {
-        let entries = fs::scan_directories(dir, show_hidden, false).unwrap_or_default();
-        let items: Vec<FuzzyItem> = entries.into_iter().map(|e| FuzzyItem::new(e, 0)).collect();
+/// Wait this long after an outside change before reloading, so a burst of changes
+/// (a checkout, an extracted archive) causes a single reload
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
 
-        Self {
-            search_query: String::new(),
-            all_items: items.clone(),
-            items,
+/// A walk running on a background thread, see `fs::spawn_walk`
+#[derive(Debug)]
+struct Scan {
+    batches: Receiver<Vec<DirEntry>>,
+    started: Instant,
+}
+
+impl FuzzyState {
+    /// Create a new FuzzyState and start scanning the given directory
+    ///
+    /// Entries arrive in the background; call `poll_scan` (or `wait_for_scan`) to pick them up.
+    pub fn new_in_dir(dir: &Path, settings: &Settings) -> Self {
+        let mut state = Self {
+            search_query: TextInput::new(),
+            all_items: Vec::new(),
+            items: Vec::new(),
             selected_index: 0,
             scroll_offset: 0,
             matcher: FuzzyMatchEngine::new(),
             current_dir: dir.to_path_buf(),
-            show_hidden,
-            show_files: false,
+            show_hidden: settings.show_hidden,
+            show_files: settings.show_files,
+            show_ignored: settings.show_ignored,
             bookmarks: Vec::new(),
+            frecent: Vec::new(),
+            ranks: HashMap::new(),
+            stale_paths: Vec::new(),
             motion_count: None,
-        }
+            page_size: settings.page_size,
+            dirs_first: settings.dirs_first,
+            sort: settings.sort(),
+            sort_reverse: settings.sort_reverse,
+            resolve_symlinks: settings.resolve_symlinks,
+            columns: settings.columns(),
+            show_preview: settings.preview,
+            preview_width: settings.preview_width,
+            preview: None,
+            theme: settings.theme(),
+            status: None,
+            deep_search: settings.deep_search,
+            search_depth: settings.search_depth,
+            exclude: settings.exclude.clone(),
+            scan: None,
+            reselect: None,
+            replace_items: false,
+            has_metadata: false,
+            watcher: None,
+            changed_at: None,
+            previewer: None,
+        };
+        state.load_dir(dir);
+        state
     }
 
     #[cfg(test)]
@@ -482,7 +893,7 @@ impl FuzzyState {
         let items: Vec<FuzzyItem> = entries.into_iter().map(|e| FuzzyItem::new(e, 0)).collect();
 
         Self {
-            search_query: String::new(),
+            search_query: TextInput::new(),
             all_items: items.clone(),
             items,
             selected_index: 0,
@@ -491,8 +902,33 @@ impl FuzzyState {
             current_dir: PathBuf::from("/"),
             show_hidden: false,
             show_files: false,
+            show_ignored: false,
             bookmarks: Vec::new(),
+            frecent: Vec::new(),
+            ranks: HashMap::new(),
+            stale_paths: Vec::new(),
             motion_count: None,
+            page_size: 10,
+            dirs_first: true,
+            sort: SortMode::Name,
+            sort_reverse: false,
+            resolve_symlinks: false,
+            columns: Vec::new(),
+            show_preview: false,
+            preview_width: 50,
+            preview: None,
+            theme: Theme::default(),
+            status: None,
+            deep_search: false,
+            search_depth: 8,
+            exclude: Vec::new(),
+            scan: None,
+            reselect: None,
+            replace_items: false,
+            has_metadata: false,
+            watcher: None,
+            changed_at: None,
+            previewer: None,
         }
     }
 
@@ -501,6 +937,24 @@ impl FuzzyState {
         self.refresh_bookmark_status();
     }
 
+    /// Remembered directories (ranked by frecency) that search can match outside `current_dir`
+    pub fn set_frecent(&mut self, frecent: Vec<DbDirEntry>) {
+        self.ranks = frecent
+            .iter()
+            .enumerate()
+            .map(|(rank, entry)| (PathBuf::from(&entry.path), rank))
+            .collect();
+        self.frecent = frecent;
+    }
+
+    /// Mark stored paths that no longer exist, as found by the background check
+    pub fn set_stale_paths(&mut self, paths: Vec<String>) {
+        self.stale_paths = paths;
+        for item in &mut self.items {
+            item.is_stale = self.stale_paths.contains(&item.path());
+        }
+    }
+
     fn refresh_bookmark_status(&mut self) {
         let update_item = |item: &mut FuzzyItem| {
             let path_str = item.entry.path.to_string_lossy().to_string();
@@ -524,13 +978,32 @@ impl FuzzyState {
     /// Navigate into the currently selected directory
     pub fn navigate_into(&mut self) {
         if let Some(item) = self.selected_item() {
-            let target = item.entry.path.clone();
+            let target = match &item.entry.link {
+                // Following a link back up the tree only makes the path longer
+                Some(Symlink {
+                    is_loop: true,
+                    resolved: Some(resolved),
+                    ..
+                }) => resolved.clone(),
+                _ => item.entry.path.clone(),
+            };
             if fs::is_accessible(&target) {
                 self.load_dir(&target);
             }
         }
     }
 
+    /// `path` as handed back on selection: canonical if `resolve_symlinks` is set,
+    /// otherwise as navigated
+    pub fn selection_path(&self, path: String) -> String {
+        if !self.resolve_symlinks {
+            return path;
+        }
+        std::fs::canonicalize(&path)
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or(path)
+    }
+
     /// Navigate to the parent directory
     pub fn navigate_back(&mut self) {
         if let Some(parent) = fs::get_safe_parent(&self.current_dir) {
@@ -538,36 +1011,254 @@ impl FuzzyState {
         }
     }
 
+    /// Start listing `dir` on a background thread; the previous scan, if any, is cancelled
     fn load_dir(&mut self, dir: &Path) {
-        let entries = fs::scan_directories(dir, self.show_hidden, self.show_files).unwrap_or_default();
-        let items: Vec<FuzzyItem> = entries
-            .into_iter()
-            .map(|e| {
-                let is_dir = e.is_dir;
-                let path_str = e.path.to_string_lossy().to_string();
-                if let Some(bm) = self.bookmarks.iter().find(|b| b.path == path_str) {
-                    FuzzyItem {
-                        entry: e,
-                        match_score: 0,
-                        is_bookmark: true,
-                        bookmark_key: bm.bookmark_key.clone(),
-                        is_dir,
-                    }
-                } else {
-                    FuzzyItem::new(e, 0)
-                }
-            })
-            .collect();
+        let max_depth = if self.deep_search {
+            self.search_depth
+        } else {
+            1
+        };
+        let options = WalkOptions {
+            max_depth,
+            show_hidden: self.show_hidden,
+            show_files: self.show_files,
+            show_ignored: self.show_ignored,
+            exclude: self.exclude.clone(),
+            read_metadata: self.sort.reads_metadata(),
+        };
+        self.has_metadata = options.read_metadata;
+        // Replacing the receiver stops the old walk at its next batch
+        self.scan = Some(Scan {
+            batches: fs::spawn_walk(dir, options),
+            started: Instant::now(),
+        });
 
+        // Deep search lists the subtree; `add_entries` watches the directories it finds
+        if self.current_dir != dir
+            || self.watcher.as_ref().map(DirWatcher::depth) != Some(max_depth)
+        {
+            self.watcher = DirWatcher::new(dir, max_depth);
+        }
+        self.changed_at = None;
         self.current_dir = dir.to_path_buf();
-        self.all_items = items;
-        self.items = self.all_items.clone();
+        self.reselect = None;
+        self.replace_items = false;
+        self.all_items.clear();
+        self.items.clear();
         self.search_query.clear();
         self.selected_index = 0;
         self.scroll_offset = 0;
         self.motion_count = None;
     }
 
+    fn make_item(&self, entry: DirEntry) -> FuzzyItem {
+        let path_str = entry.path.to_string_lossy().to_string();
+        let mut item = FuzzyItem::new(entry, 0);
+        if let Some(bm) = self.bookmarks.iter().find(|b| b.path == path_str) {
+            item.is_bookmark = true;
+            item.bookmark_key = bm.bookmark_key.clone();
+        }
+        item
+    }
+
+    /// Add entries the scan has found since the last call; true if anything changed
+    pub fn poll_scan(&mut self) -> bool {
+        let Some(scan) = &self.scan else {
+            return false;
+        };
+        let mut found = Vec::new();
+        let finished = loop {
+            match scan.batches.try_recv() {
+                Ok(batch) => found.extend(batch),
+                Err(TryRecvError::Empty) => break false,
+                Err(TryRecvError::Disconnected) => break true,
+            }
+        };
+        if finished {
+            self.scan = None;
+        }
+        let changed = !found.is_empty();
+        if changed || finished {
+            self.add_entries(found);
+        }
+        changed || finished
+    }
+
+    /// Block until the scan is done and add everything it found
+    pub fn wait_for_scan(&mut self) {
+        if let Some(scan) = self.scan.take() {
+            let found = scan.batches.iter().flatten().collect();
+            self.add_entries(found);
+        }
+    }
+
+    /// Reload once outside changes have settled for `WATCH_DEBOUNCE`; true if it did
+    pub fn poll_watch(&mut self) -> bool {
+        if self.watcher.as_ref().is_some_and(DirWatcher::changed) {
+            self.changed_at.get_or_insert_with(Instant::now);
+        }
+        match self.changed_at {
+            Some(at) if at.elapsed() >= WATCH_DEBOUNCE => {
+                self.reload();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Ask for a preview of the selected entry and pick up a finished one; true if a new
+    /// preview arrived
+    pub fn poll_preview(&mut self) -> bool {
+        let Some(previewer) = self.request_preview() else {
+            return false;
+        };
+        match previewer.poll() {
+            Some(preview) => {
+                self.preview = Some(preview);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Block until the selected entry's preview is built
+    pub fn wait_for_preview(&mut self) {
+        if let Some(preview) = self.request_preview().and_then(|p| p.wait()) {
+            self.preview = Some(preview);
+        }
+    }
+
+    /// Send the selected entry to the previewer, starting it if needed
+    fn request_preview(&mut self) -> Option<&mut Previewer> {
+        if !self.show_preview {
+            return None;
+        }
+        let (path, is_dir) = self
+            .selected_item()
+            .map(|item| (item.entry.path.clone(), item.is_dir))?;
+        let previewer = self
+            .previewer
+            .get_or_insert_with(|| Previewer::spawn(PREVIEW_LINES));
+        previewer.request(&path, is_dir);
+        Some(previewer)
+    }
+
+    /// The preview belongs to the selected entry
+    pub fn preview_is_current(&self) -> bool {
+        match (&self.preview, self.selected_item()) {
+            (Some(preview), Some(item)) => preview.path == item.entry.path,
+            _ => false,
+        }
+    }
+
+    /// A directory scan is still running
+    pub fn is_scanning(&self) -> bool {
+        self.scan.is_some()
+    }
+
+    /// How long the running scan has taken so far
+    pub fn scan_elapsed(&self) -> Option<Duration> {
+        self.scan.as_ref().map(|scan| scan.started.elapsed())
+    }
+
+    /// Merge streamed entries, keeping the selection on the same item
+    ///
+    /// The batch is sorted and matched on its own, then merged into the sorted lists,
+    /// so a long scan costs a merge per batch rather than a full re-sort.
+    fn add_entries(&mut self, entries: Vec<DirEntry>) {
+        if let Some(watcher) = &mut self.watcher {
+            watcher.watch_entries(&entries);
+        }
+        let mut added: Vec<FuzzyItem> = entries.into_iter().map(|e| self.make_item(e)).collect();
+        if std::mem::take(&mut self.replace_items) {
+            self.all_items = added;
+            self.sort_items();
+            return;
+        }
+        if added.is_empty() {
+            return;
+        }
+        let selected = self.selection_to_keep();
+
+        let order = self.sort_order();
+        let ranks = &self.ranks;
+        let compare = |a: &FuzzyItem, b: &FuzzyItem| {
+            b.match_score
+                .cmp(&a.match_score)
+                .then_with(|| order.compare(&a.entry, &b.entry, ranks))
+        };
+        added.sort_by(compare);
+        let all_items = std::mem::take(&mut self.all_items);
+        self.all_items = merge_sorted(all_items, added.clone(), compare);
+
+        if !self.search_query.is_empty() {
+            let query = Query::parse(&self.search_query);
+            added = added
+                .into_iter()
+                .filter_map(|mut item| {
+                    let (score, indices) = query.matches(&self.matcher, &item.entry.name)?;
+                    item.match_score = score;
+                    item.match_indices = indices;
+                    item.is_stale = self.stale_paths.contains(&item.path());
+                    Some(item)
+                })
+                .collect();
+            added.sort_by(compare);
+            // A bookmark or remembered directory listed on its own is now in the listing
+            let paths: HashSet<&Path> = added.iter().map(|a| a.entry.path.as_path()).collect();
+            self.items
+                .retain(|item| !item.standalone || !paths.contains(item.entry.path.as_path()));
+        }
+        let items = std::mem::take(&mut self.items);
+        self.items = merge_sorted(items, added, compare);
+        self.restore_selection(selected);
+    }
+
+    /// The active ordering; deep search keeps subdirectories next to their parents
+    fn sort_order(&self) -> SortOrder {
+        SortOrder {
+            mode: self.sort,
+            reverse: self.sort_reverse,
+            dirs_first: self.dirs_first && !self.deep_search,
+        }
+    }
+
+    /// Re-sort and re-filter, keeping the selected entry (or the one a reload is
+    /// looking for) under the cursor
+    fn sort_items(&mut self) {
+        let order = self.sort_order();
+        let ranks = &self.ranks;
+        self.all_items
+            .sort_by(|a, b| order.compare(&a.entry, &b.entry, ranks));
+
+        let selected = self.selection_to_keep();
+        self.filter_results();
+        self.restore_selection(selected);
+    }
+
+    /// The entry to keep under the cursor when the list changes, with its scroll offset
+    fn selection_to_keep(&self) -> Option<(PathBuf, usize)> {
+        match self.reselect.clone() {
+            Some(reselect) => Some(reselect),
+            None => self
+                .selected_item()
+                .map(|item| (item.entry.path.clone(), self.scroll_offset)),
+        }
+    }
+
+    /// Put the cursor back on `selected` if it is listed
+    fn restore_selection(&mut self, selected: Option<(PathBuf, usize)>) {
+        let Some((path, scroll_offset)) = selected else {
+            return;
+        };
+        if let Some(index) = self.items.iter().position(|item| item.entry.path == path) {
+            self.reselect = None;
+            self.selected_index = index;
+            self.scroll_offset = scroll_offset;
+            self.update_scroll();
+        }
+    }
+
     pub fn set_motion_count(&mut self, count: usize) {
         self.motion_count = Some(count);
     }
@@ -577,22 +1268,31 @@ impl FuzzyState {
     }
 
     pub fn set_query(&mut self, query: &str) {
-        self.search_query = query.to_string();
+        self.search_query.set(query);
         self.filter_results();
     }
 
-    /// Add character to search query
+    /// Insert a character into the search query at the cursor
     pub fn add_char(&mut self, c: char) {
-        self.search_query.push(c);
+        self.search_query.insert(c);
         self.filter_results();
     }
 
-    /// Remove last character from search query
+    /// Remove the character before the cursor from the search query
     pub fn pop_char(&mut self) {
-        self.search_query.pop();
+        self.search_query.backspace();
         self.filter_results();
     }
 
+    /// Apply a cursor movement or edit to the search query, refiltering if the text changed
+    pub fn edit_query(&mut self, edit: impl FnOnce(&mut TextInput)) {
+        let before = self.search_query.to_string();
+        edit(&mut self.search_query);
+        if *self.search_query != *before {
+            self.filter_results();
+        }
+    }
+
     /// Clear search query
     pub fn clear_query(&mut self) {
         self.search_query.clear();
@@ -612,7 +1312,8 @@ impl FuzzyState {
             return;
         }
 
-        let pattern = &self.search_query;
+        // Extended syntax, see `fuzzy::query`; deep search names are relative paths
+        let query = Query::parse(&self.search_query);
         let matcher = &self.matcher;
 
         // Filter directory items - preserve original item data, only update score
@@ -620,11 +1321,12 @@ impl FuzzyState {
             .all_items
             .iter()
             .filter_map(|item| {
-                matcher
-                    .get_score(pattern, &item.entry.name)
-                    .map(|score| {
+                query
+                    .matches(matcher, &item.entry.name)
+                    .map(|(score, indices)| {
                         let mut result = item.clone();
                         result.match_score = score;
+                        result.match_indices = indices;
                         result
                     })
             })
@@ -632,16 +1334,16 @@ impl FuzzyState {
 
         // Also match bookmarks by name and alias
         for bm in &self.bookmarks {
-            let name_score = matcher.get_score(pattern, &bm.name);
-            let alias_score = bm
+            let name_match = query.matches(matcher, &bm.name);
+            let alias_match = bm
                 .bookmark_key
                 .as_deref()
-                .and_then(|key| matcher.get_score(pattern, key));
+                .and_then(|key| query.matches(matcher, key));
 
-            let best_score = match (name_score, alias_score) {
-                (Some(a), Some(b)) => Some(a.max(b)),
-                (Some(a), None) => Some(a),
-                (None, Some(b)) => Some(b),
+            let best_score = match (&name_match, &alias_match) {
+                (Some((a, _)), Some((b, _))) => Some(*a.max(b)),
+                (Some((a, _)), None) => Some(*a),
+                (None, Some((b, _))) => Some(*b),
                 (None, None) => None,
             };
 
@@ -650,15 +1352,37 @@ impl FuzzyState {
                 if !filtered.iter().any(|item| item.entry.path == bm.path) {
                     let mut bm_item = FuzzyItem::from_bookmark(bm);
                     bm_item.match_score = score;
+                    bm_item.match_indices = name_match.map(|(_, i)| i).unwrap_or_default();
+                    bm_item.key_indices = alias_match.map(|(_, i)| i).unwrap_or_default();
                     filtered.push(bm_item);
                 }
             }
         }
 
+        // Remembered directories from the visit history
+        for visited in &self.frecent {
+            if let Some((score, indices)) = query.matches(matcher, &visited.name) {
+                if !filtered.iter().any(|item| item.entry.path == visited.path) {
+                    let mut item = FuzzyItem::from_visited(visited);
+                    item.match_score = score;
+                    item.match_indices = indices;
+                    filtered.push(item);
+                }
+            }
+        }
+
+        if !self.stale_paths.is_empty() {
+            for item in &mut filtered {
+                item.is_stale = self.stale_paths.contains(&item.path());
+            }
+        }
+
+        let order = self.sort_order();
+        let ranks = &self.ranks;
         filtered.sort_by(|a, b| {
             b.match_score
                 .cmp(&a.match_score)
-                .then_with(|| a.entry.name.cmp(&b.entry.name))
+                .then_with(|| order.compare(&a.entry, &b.entry, ranks))
         });
 
         self.items = filtered;
@@ -689,7 +1413,7 @@ impl FuzzyState {
 
     /// Scroll up by one page
     pub fn page_up(&mut self) {
-        let page_size = 10;
+        let page_size = self.page_size;
         if self.selected_index >= page_size {
             self.selected_index -= page_size;
         } else {
@@ -700,7 +1424,7 @@ impl FuzzyState {
 
     /// Scroll down by one page
     pub fn page_down(&mut self) {
-        let page_size = 10;
+        let page_size = self.page_size;
         let max_index = self.items.len().saturating_sub(1);
         if self.selected_index + page_size <= max_index {
             self.selected_index += page_size;
@@ -746,10 +1470,83 @@ impl FuzzyState {
         self.load_dir(&dir);
     }
 
-    /// Reload the current directory
+    /// Toggle listing of gitignored entries and reload directory
+    pub fn toggle_ignored(&mut self) {
+        self.show_ignored = !self.show_ignored;
+        let dir = self.current_dir.clone();
+        self.load_dir(&dir);
+    }
+
+    /// Show or hide a metadata column, keeping the display order
+    pub fn toggle_column(&mut self, column: Column) {
+        let shown = !self.columns.contains(&column);
+        self.columns = Column::ALL
+            .into_iter()
+            .filter(|c| {
+                if *c == column {
+                    shown
+                } else {
+                    self.columns.contains(c)
+                }
+            })
+            .collect();
+    }
+
+    /// Show or hide the preview pane
+    pub fn toggle_preview(&mut self) {
+        self.show_preview = !self.show_preview;
+    }
+
+    /// Switch between listing the current directory and its whole subtree
+    pub fn toggle_deep(&mut self) {
+        self.deep_search = !self.deep_search;
+        let dir = self.current_dir.clone();
+        self.load_dir(&dir);
+    }
+
+    /// Step to the next sort mode
+    pub fn cycle_sort(&mut self) {
+        self.sort = self.sort.next();
+        // Walk again to read metadata off the UI thread rather than stat every entry here
+        if self.sort.reads_metadata() && !self.has_metadata {
+            self.reload();
+        } else {
+            self.sort_items();
+        }
+    }
+
+    /// Flip the sort direction; directories stay first
+    pub fn reverse_sort(&mut self) {
+        self.sort_reverse = !self.sort_reverse;
+        self.sort_items();
+    }
+
+    /// Reload the current directory, keeping the query and the cursor on the selected
+    /// entry; the old entries stay listed until the new scan delivers
     pub fn reload(&mut self) {
+        let selected = self
+            .selected_item()
+            .map(|item| (item.entry.path.clone(), self.scroll_offset));
+        let query = self.search_query.clone();
+        let (all_items, items) = (
+            std::mem::take(&mut self.all_items),
+            std::mem::take(&mut self.items),
+        );
+        let (selected_index, scroll_offset) = (self.selected_index, self.scroll_offset);
+
         let dir = self.current_dir.clone();
         self.load_dir(&dir);
+        self.search_query = query;
+        self.all_items = all_items;
+        self.items = items;
+        self.selected_index = selected_index;
+        self.scroll_offset = scroll_offset;
+        self.reselect = selected;
+        self.replace_items = true;
+        // Contents may have changed, so build the selected entry's preview again
+        if let Some(previewer) = &mut self.previewer {
+            previewer.invalidate();
+        }
     }
 
     /// Get number of results
@@ -768,6 +1565,8 @@ mod tests {
             path: PathBuf::from(format!("/test/{}", name)),
             name: name.to_string(),
             is_dir: true,
+            link: None,
+            meta: Default::default(),
         }
     }
 
@@ -818,6 +1617,28 @@ mod tests {
         assert_eq!(state.search_query, "a");
     }
 
+    #[test]
+    fn test_edit_query_at_cursor() {
+        let entries = vec![test_entry("apple"), test_entry("banana")];
+        let mut state = FuzzyState::with_entries(entries);
+
+        state.set_query("bna");
+        assert_eq!(state.result_count(), 1);
+        state.edit_query(|input| {
+            input.move_home();
+            input.move_right();
+        });
+        state.add_char('a');
+        assert_eq!(state.search_query, "bana");
+
+        state.edit_query(|input| {
+            input.move_right();
+            input.delete_to_start();
+        });
+        assert_eq!(state.search_query, "a");
+        assert_eq!(state.result_count(), 2);
+    }
+
     #[test]
     fn test_fuzzy_state_clear_query_restores_all_items() {
         let entries = vec![
@@ -898,6 +1719,20 @@ mod tests {
         assert_eq!(state.selected_item().unwrap().entry.name, "banana");
     }
 
+    #[test]
+    fn test_page_size_from_settings() {
+        let entries = (0..30)
+            .map(|i| test_entry(&format!("dir{:02}", i)))
+            .collect();
+        let mut state = FuzzyState::with_entries(entries);
+        state.page_size = 4;
+
+        state.page_down();
+        assert_eq!(state.selected_index, 4);
+        state.page_up();
+        assert_eq!(state.selected_index, 0);
+    }
+
     #[test]
     fn test_bookmark_search_results() {
         let entries = vec![test_entry("projects"), test_entry("documents")];
@@ -908,13 +1743,443 @@ mod tests {
             name: "work".to_string(),
             is_bookmark: true,
             bookmark_key: Some("w".to_string()),
+            access_count: 0,
+            last_accessed: None,
         }]);
 
         // Search for "w" should find the bookmark
         state.add_char('w');
-        assert!(state
+        let bookmark = state
+            .items
+            .iter()
+            .find(|i| i.is_bookmark && i.entry.name == "work")
+            .unwrap();
+        // Both the name and the alias matched
+        assert_eq!(bookmark.match_indices, [0]);
+        assert_eq!(bookmark.key_indices, [0]);
+
+        state.set_query("jcs");
+        assert_eq!(state.items[0].entry.name, "projects");
+        assert_eq!(state.items[0].match_indices, [3, 5, 7]);
+        state.clear_query();
+        assert!(state.items.iter().all(|i| i.match_indices.is_empty()));
+    }
+
+    #[test]
+    fn test_extended_query_syntax() {
+        // Deep search lists relative paths as names
+        let entries = ["src", "src/ui", "tests/ui", "docs"]
+            .map(test_entry)
+            .to_vec();
+        let mut state = FuzzyState::with_entries(entries);
+        state.set_bookmarks(vec![DbDirEntry {
+            path: "/home/user/work".to_string(),
+            name: "work".to_string(),
+            is_bookmark: true,
+            bookmark_key: Some("ui".to_string()),
+            access_count: 0,
+            last_accessed: None,
+        }]);
+        let names = |state: &FuzzyState| -> Vec<String> {
+            let mut names: Vec<String> = state.items.iter().map(|i| i.entry.name.clone()).collect();
+            names.sort();
+            names
+        };
+
+        state.set_query("ui$ !^tests");
+        assert_eq!(names(&state), ["src/ui", "work"]);
+        let bookmark = state.items.iter().find(|i| i.is_bookmark).unwrap();
+        assert_eq!(bookmark.key_indices, [0, 1]);
+        assert!(bookmark.match_indices.is_empty());
+
+        state.set_query("^src$ | ^docs");
+        assert_eq!(names(&state), ["docs", "src"]);
+
+        state.set_query("'/u");
+        assert_eq!(names(&state), ["src/ui", "tests/ui"]);
+        let item = state
             .items
             .iter()
-            .any(|i| i.is_bookmark && i.entry.name == "work"));
+            .find(|i| i.entry.name == "src/ui")
+            .unwrap();
+        assert_eq!(item.match_indices, [3, 4]);
+    }
+
+    #[test]
+    fn test_highlight_splits_matched_runs() {
+        let style = Style::default();
+        let spans = highlight("día-src", &[1, 2, 4], style, Color::Cyan);
+        let texts: Vec<&str> = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(texts, ["d", "ía", "-", "s", "rc"]);
+        assert_eq!(spans[1].style.fg, Some(Color::Cyan));
+        assert_eq!(spans[2].style, style);
+        assert_eq!(highlight("src", &[], style, Color::Cyan).len(), 1);
+    }
+
+    #[test]
+    fn test_frecent_search_results() {
+        let entries = vec![test_entry("projects")];
+        let mut state = FuzzyState::with_entries(entries);
+
+        state.set_frecent(vec![DbDirEntry {
+            path: "/srv/deploy".to_string(),
+            name: "deploy".to_string(),
+            is_bookmark: false,
+            bookmark_key: None,
+            access_count: 4,
+            last_accessed: Some(0),
+        }]);
+
+        state.set_query("dep");
+        assert_eq!(state.result_count(), 1);
+        assert_eq!(state.items[0].path(), "/srv/deploy");
+    }
+
+    #[test]
+    fn test_stale_bookmarks_are_flagged() {
+        let mut state = FuzzyState::with_entries(vec![]);
+        state.set_bookmarks(vec![DbDirEntry {
+            path: "/gone/work".to_string(),
+            name: "work".to_string(),
+            is_bookmark: true,
+            bookmark_key: Some("w".to_string()),
+            access_count: 0,
+            last_accessed: None,
+        }]);
+
+        state.set_query("work");
+        assert!(!state.items[0].is_stale);
+
+        state.set_stale_paths(vec!["/gone/work".to_string()]);
+        assert!(state.items[0].is_stale);
+        assert_eq!(state.search_query, "work");
+    }
+
+    #[test]
+    fn test_deep_search_streams_relative_paths() {
+        let root = std::env::temp_dir().join("jump_test_deep_search");
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["src/ui", "node_modules/pkg", "docs"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+
+        let settings = Settings {
+            deep_search: true,
+            exclude: vec!["node_modules".to_string()],
+            ..Settings::default()
+        };
+        let mut state = FuzzyState::new_in_dir(&root, &settings);
+        state.wait_for_scan();
+        assert!(!state.is_scanning());
+        let names: Vec<&str> = state.items.iter().map(|i| i.entry.name.as_str()).collect();
+        assert_eq!(names, ["docs", "src", "src/ui"]);
+
+        state.set_query("srcui");
+        assert_eq!(state.items[0].path(), root.join("src/ui").to_string_lossy());
+
+        // Back to a single level, still without excluded names
+        state.toggle_deep();
+        state.wait_for_scan();
+        let names: Vec<&str> = state
+            .all_items
+            .iter()
+            .map(|i| i.entry.name.as_str())
+            .collect();
+        assert_eq!(names, ["docs", "src"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_enter_symlinked_directories() {
+        let root = std::env::temp_dir().join("jump_test_enter_symlinks");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("real/inner")).unwrap();
+        std::os::unix::fs::symlink("real", root.join("linked")).unwrap();
+        std::os::unix::fs::symlink(".", root.join("real/again")).unwrap();
+        let real = std::fs::canonicalize(root.join("real")).unwrap();
+
+        let mut state = FuzzyState::new_in_dir(&root, &Settings::default());
+        state.wait_for_scan();
+        state.set_query("linked");
+        state.navigate_into();
+        state.wait_for_scan();
+        assert_eq!(state.current_dir, root.join("linked"));
+        let names: Vec<&str> = state.items.iter().map(|i| i.entry.name.as_str()).collect();
+        assert_eq!(names, ["again", "inner"]);
+        assert_eq!(
+            state.selection_path(state.items[1].path()),
+            root.join("linked/inner").to_string_lossy()
+        );
+
+        // A link back to its own directory opens the target instead of nesting
+        state.navigate_into();
+        state.wait_for_scan();
+        assert_eq!(state.current_dir, real);
+
+        state.resolve_symlinks = true;
+        let selected = state.selection_path(root.join("linked/inner").to_string_lossy().into());
+        assert_eq!(selected, real.join("inner").to_string_lossy());
+    }
+
+    #[test]
+    fn test_sort_and_reload_keep_cursor() {
+        let root = std::env::temp_dir().join("jump_test_sort_reload");
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["v10", "v9", "V2"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join("v9/a"), "").unwrap();
+
+        let mut state = FuzzyState::new_in_dir(&root, &Settings::default());
+        state.wait_for_scan();
+        let names = |state: &FuzzyState| -> Vec<String> {
+            state.items.iter().map(|i| i.entry.name.clone()).collect()
+        };
+        assert_eq!(names(&state), ["V2", "v9", "v10"]);
+
+        state.move_down();
+        state.reverse_sort();
+        assert_eq!(names(&state), ["v10", "v9", "V2"]);
+        assert_eq!(state.selected_item().unwrap().entry.name, "v9");
+
+        // Size sorts directories by item count; ties fall back to name
+        state.cycle_sort();
+        state.cycle_sort();
+        assert_eq!(state.sort, SortMode::Size);
+        assert_eq!(names(&state), ["v10", "V2", "v9"]);
+
+        std::fs::create_dir(root.join("v1")).unwrap();
+        state.reload();
+        state.wait_for_scan();
+        assert_eq!(names(&state), ["v10", "V2", "v1", "v9"]);
+        assert_eq!(state.selected_item().unwrap().entry.name, "v9");
+    }
+
+    #[test]
+    fn test_preview_follows_selection_and_reload() {
+        let root = std::env::temp_dir().join("jump_test_preview_state");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("a/inner")).unwrap();
+        std::fs::create_dir_all(root.join("b")).unwrap();
+
+        let mut state = FuzzyState::new_in_dir(&root, &Settings::default());
+        state.wait_for_scan();
+        state.wait_for_preview();
+        assert!(state.preview.is_none());
+
+        state.toggle_preview();
+        state.wait_for_preview();
+        assert!(state.preview_is_current());
+        let children = |state: &FuzzyState| match &state.preview.as_ref().unwrap().body {
+            PreviewBody::Children { total, .. } => *total,
+            body => panic!("{:?}", body),
+        };
+        assert_eq!(children(&state), 1);
+
+        // The old preview stays until the new one arrives
+        state.move_down();
+        assert!(!state.preview_is_current());
+        state.wait_for_preview();
+        assert_eq!(state.preview.as_ref().unwrap().path, root.join("b"));
+        assert_eq!(children(&state), 0);
+
+        std::fs::create_dir(root.join("b/new")).unwrap();
+        state.reload();
+        state.wait_for_scan();
+        state.wait_for_preview();
+        assert_eq!(children(&state), 1);
+    }
+
+    #[test]
+    fn test_outside_changes_reload_keeping_query_and_selection() {
+        let root = std::env::temp_dir().join("jump_test_watch");
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["app_one", "app_two", "docs"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+
+        let mut state = FuzzyState::new_in_dir(&root, &Settings::default());
+        state.wait_for_scan();
+        state.set_query("app");
+        state.move_down();
+        assert_eq!(state.selected_item().unwrap().entry.name, "app_two");
+
+        std::fs::create_dir(root.join("app_three")).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !state.poll_watch() {
+            assert!(Instant::now() < deadline, "no reload after mkdir");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        // Old entries stay listed until the scan delivers
+        assert_eq!(state.result_count(), 2);
+        state.wait_for_scan();
+
+        assert_eq!(state.search_query, "app");
+        assert_eq!(state.result_count(), 3);
+        assert_eq!(state.selected_item().unwrap().entry.name, "app_two");
+        assert!(!state.poll_watch());
+    }
+
+    #[test]
+    fn test_outside_changes_are_debounced() {
+        let root = std::env::temp_dir().join("jump_test_watch_debounce");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+
+        let mut state = FuzzyState::new_in_dir(&root, &Settings::default());
+        state.wait_for_scan();
+
+        // A burst of changes waits out the debounce and reloads once
+        for i in 0..5 {
+            std::fs::create_dir(root.join(format!("new_{}", i))).unwrap();
+        }
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while state.changed_at.is_none() {
+            assert!(Instant::now() < deadline, "no change noticed after mkdir");
+            assert!(!state.poll_watch());
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(!state.poll_watch());
+        std::thread::sleep(WATCH_DEBOUNCE);
+        assert!(state.poll_watch());
+        state.wait_for_scan();
+        assert_eq!(state.result_count(), 6);
+        assert!(!state.poll_watch());
+    }
+
+    #[test]
+    fn test_deep_search_watches_subtree_keeping_selection() {
+        let root = std::env::temp_dir().join("jump_test_watch_deep");
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["a/b", "c", "target/debug"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+
+        let settings = Settings {
+            deep_search: true,
+            exclude: vec!["target".to_string()],
+            ..Settings::default()
+        };
+        let mut state = FuzzyState::new_in_dir(&root, &settings);
+        state.wait_for_scan();
+        state.move_down();
+        state.move_down();
+        assert_eq!(state.selected_item().unwrap().entry.name, "c");
+
+        // Excluded subtrees aren't listed, so they aren't watched either
+        std::fs::create_dir(root.join("target/debug/build")).unwrap();
+        std::thread::sleep(WATCH_DEBOUNCE * 2);
+        assert!(!state.poll_watch());
+
+        std::fs::create_dir(root.join("a/b/new")).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !state.poll_watch() {
+            assert!(Instant::now() < deadline, "no reload after nested mkdir");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        state.wait_for_scan();
+
+        assert_eq!(state.result_count(), 4);
+        assert_eq!(state.selected_item().unwrap().entry.name, "c");
+    }
+
+    #[test]
+    fn test_scan_runs_in_background_and_cancels() {
+        let root = std::env::temp_dir().join("jump_test_background_scan");
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["one/Beta", "one/alpha", "two/gamma"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join("one/a.txt"), "").unwrap();
+
+        let settings = Settings {
+            show_files: true,
+            ..Settings::default()
+        };
+        let mut state = FuzzyState::new_in_dir(&root.join("one"), &settings);
+        assert!(state.is_scanning());
+        state.wait_for_scan();
+        let names: Vec<&str> = state.items.iter().map(|i| i.entry.name.as_str()).collect();
+        assert_eq!(names, ["alpha", "Beta", "a.txt"]);
+
+        // Moving on before a scan is picked up drops its results
+        state.load_dir(&root.join("one"));
+        state.load_dir(&root.join("two"));
+        assert!(state.items.is_empty());
+        while state.is_scanning() {
+            state.poll_scan();
+        }
+        assert_eq!(state.result_count(), 1);
+        assert_eq!(state.items[0].entry.name, "gamma");
+    }
+
+    #[test]
+    fn test_streamed_batches_match_full_sort() {
+        let names = ["v10", "b", "v9", "a", "alps", "c", "alpha", "v1"];
+        let names_of = |items: &[FuzzyItem]| -> Vec<String> {
+            items.iter().map(|i| i.entry.name.clone()).collect()
+        };
+        let bookmark = DbDirEntry {
+            path: "/test/alps".to_string(),
+            name: "alps".to_string(),
+            is_bookmark: true,
+            bookmark_key: Some("p".to_string()),
+            access_count: 0,
+            last_accessed: None,
+        };
+        for query in ["", "v", "al"] {
+            let mut full = FuzzyState::with_entries(names.map(test_entry).to_vec());
+            full.set_bookmarks(vec![bookmark.clone()]);
+            full.set_query(query);
+            full.sort_items();
+
+            // The bookmark is listed on its own until its directory streams in
+            let mut streamed = FuzzyState::with_entries(Vec::new());
+            streamed.set_bookmarks(vec![bookmark.clone()]);
+            streamed.set_query(query);
+            for batch in names.chunks(3) {
+                streamed.add_entries(batch.iter().copied().map(test_entry).collect());
+            }
+            assert_eq!(names_of(&streamed.all_items), names_of(&full.all_items));
+            assert_eq!(
+                names_of(&streamed.items),
+                names_of(&full.items),
+                "{}",
+                query
+            );
+        }
+    }
+
+    #[test]
+    fn test_metadata_sort_reads_metadata_in_walk() {
+        let root = std::env::temp_dir().join("jump_test_sort_metadata");
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["a", "b"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+
+        let mut state = FuzzyState::new_in_dir(&root, &Settings::default());
+        state.wait_for_scan();
+        assert!(state.all_items.iter().all(|i| i.entry.meta.get().is_none()));
+
+        // Name -> modified walks again instead of reading metadata here
+        state.cycle_sort();
+        assert_eq!(state.sort, SortMode::Modified);
+        assert!(state.is_scanning());
+        state.wait_for_scan();
+        assert_eq!(state.result_count(), 2);
+        assert!(state.all_items.iter().all(|i| i.entry.meta.get().is_some()));
+    }
+
+    #[test]
+    fn test_streamed_entries_keep_selection() {
+        let mut state = FuzzyState::with_entries(vec![test_entry("alpha"), test_entry("alps")]);
+        state.set_query("alp");
+        state.move_down();
+        let selected = state.selected_item().unwrap().path();
+
+        state.add_entries(vec![test_entry("alp")]);
+        assert_eq!(state.result_count(), 3);
+        assert_eq!(state.selected_item().unwrap().path(), selected);
     }
 }
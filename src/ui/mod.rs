@@ -1,3 +1,13 @@
+//! Ratatui rendering and the state behind it
+
+mod columns;
 mod fuzzy;
+mod sort;
+mod text_input;
+mod theme;
 
+pub use columns::Column;
 pub use fuzzy::{draw_fuzzy, FuzzyState};
+pub use sort::{merge_sorted, natural_cmp, SortMode, SortOrder};
+pub use text_input::TextInput;
+pub use theme::Theme;
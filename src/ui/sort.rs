@@ -0,0 +1,267 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::fs::DirEntry;
+
+/// What the listing is ordered by, cycled with `s`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SortMode {
+    /// Natural order: `v2` before `v10`, case-insensitive
+    #[default]
+    Name,
+    /// Most recently modified first
+    Modified,
+    /// Largest first; directories by item count
+    Size,
+    /// Grouped by extension, then by name
+    Extension,
+    /// Most visited first, by the frecency ranking
+    Frecency,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 5] = [
+        SortMode::Name,
+        SortMode::Modified,
+        SortMode::Size,
+        SortMode::Extension,
+        SortMode::Frecency,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SortMode::Name => "name",
+            SortMode::Modified => "modified",
+            SortMode::Size => "size",
+            SortMode::Extension => "extension",
+            SortMode::Frecency => "frecency",
+        }
+    }
+
+    /// Orders by metadata, which the walk then reads off the UI thread
+    pub fn reads_metadata(self) -> bool {
+        matches!(self, SortMode::Modified | SortMode::Size)
+    }
+
+    /// The mode after this one, wrapping around
+    pub fn next(self) -> Self {
+        let index = SortMode::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        SortMode::ALL[(index + 1) % SortMode::ALL.len()]
+    }
+}
+
+impl fmt::Display for SortMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SortMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SortMode::ALL
+            .into_iter()
+            .find(|mode| mode.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = SortMode::ALL.iter().map(|m| m.name()).collect();
+                format!("unknown sort '{}' (expected {})", s, names.join(", "))
+            })
+    }
+}
+
+/// A total order over entries, so equal keys never swap places between sorts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortOrder {
+    pub mode: SortMode,
+    pub reverse: bool,
+    /// Directories before files whatever the mode; not reversed
+    pub dirs_first: bool,
+}
+
+impl SortOrder {
+    /// Compare two entries; `ranks` maps remembered paths to their frecency rank
+    ///
+    /// Modified and size read each entry's metadata, see `DirEntry::metadata`.
+    pub fn compare(&self, a: &DirEntry, b: &DirEntry, ranks: &HashMap<PathBuf, usize>) -> Ordering {
+        let kind = if self.dirs_first {
+            b.is_dir.cmp(&a.is_dir)
+        } else {
+            Ordering::Equal
+        };
+        let key = match self.mode {
+            SortMode::Name => Ordering::Equal,
+            SortMode::Modified => {
+                let modified = |e: &DirEntry| e.metadata().and_then(|meta| meta.modified);
+                modified(b).cmp(&modified(a))
+            }
+            SortMode::Size => size(b).cmp(&size(a)),
+            SortMode::Extension => extension(a).cmp(&extension(b)),
+            SortMode::Frecency => {
+                let rank = |e: &DirEntry| ranks.get(&e.path).copied().unwrap_or(usize::MAX);
+                rank(a).cmp(&rank(b))
+            }
+        };
+        let by_mode = key
+            .then_with(|| natural_cmp(&a.name, &b.name))
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.path.cmp(&b.path));
+        kind.then(if self.reverse {
+            by_mode.reverse()
+        } else {
+            by_mode
+        })
+    }
+}
+
+/// Merge two lists already sorted by `compare` into one; `a` goes first among equals
+pub fn merge_sorted<T>(a: Vec<T>, b: Vec<T>, compare: impl Fn(&T, &T) -> Ordering) -> Vec<T> {
+    let mut merged = Vec::with_capacity(a.len() + b.len());
+    let (mut a, mut b) = (a.into_iter().peekable(), b.into_iter().peekable());
+    while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+        let next = if compare(y, x) == Ordering::Less {
+            b.next()
+        } else {
+            a.next()
+        };
+        merged.extend(next);
+    }
+    merged.extend(a);
+    merged.extend(b);
+    merged
+}
+
+/// Bytes for files, children for directories
+fn size(entry: &DirEntry) -> u64 {
+    entry.metadata().map_or(0, |meta| match meta.item_count {
+        Some(count) if entry.is_dir => count as u64,
+        _ => meta.size,
+    })
+}
+
+/// Lowercase extension of a file; directories have none
+fn extension(entry: &DirEntry) -> String {
+    if entry.is_dir {
+        return String::new();
+    }
+    entry
+        .path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Compare case-insensitively with runs of digits compared as numbers, so `file2` sorts
+/// before `file10` and `v1.9` before `v1.10`
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+    loop {
+        let (Some(x), Some(y)) = (a.chars().next(), b.chars().next()) else {
+            return a.len().cmp(&b.len());
+        };
+        if x.is_ascii_digit() && y.is_ascii_digit() {
+            let (digits_a, rest_a) = split_digits(a);
+            let (digits_b, rest_b) = split_digits(b);
+            let (number_a, number_b) = (
+                digits_a.trim_start_matches('0'),
+                digits_b.trim_start_matches('0'),
+            );
+            let ord = number_a
+                .len()
+                .cmp(&number_b.len())
+                .then_with(|| number_a.cmp(number_b));
+            if ord != Ordering::Equal {
+                return ord;
+            }
+            (a, b) = (rest_a, rest_b);
+        } else {
+            let ord = x.to_lowercase().cmp(y.to_lowercase());
+            if ord != Ordering::Equal {
+                return ord;
+            }
+            (a, b) = (&a[x.len_utf8()..], &b[y.len_utf8()..]);
+        }
+    }
+}
+
+/// Leading ASCII digits of `s` and the rest
+fn split_digits(s: &str) -> (&str, &str) {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s.split_at(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, is_dir: bool) -> DirEntry {
+        DirEntry {
+            path: PathBuf::from("/test").join(name),
+            name: name.to_string(),
+            is_dir,
+            link: None,
+            meta: Default::default(),
+        }
+    }
+
+    fn sorted(order: SortOrder, names: &[(&str, bool)]) -> Vec<String> {
+        let ranks = HashMap::from([
+            (PathBuf::from("/test/b.rs"), 0),
+            (PathBuf::from("/test/src"), 1),
+        ]);
+        let mut entries: Vec<DirEntry> = names.iter().map(|(n, d)| entry(n, *d)).collect();
+        entries.sort_by(|a, b| order.compare(a, b, &ranks));
+        entries.into_iter().map(|e| e.name).collect()
+    }
+
+    #[test]
+    fn test_natural_cmp() {
+        let mut names = ["v1.10", "file10", "File2", "v1.9", "file02b", "file"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(
+            names,
+            ["file", "File2", "file02b", "file10", "v1.9", "v1.10"]
+        );
+        assert_eq!(natural_cmp("a", "A"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_merge_sorted() {
+        let merged = merge_sorted(vec![1, 4, 4, 9], vec![0, 4, 5, 12], |a, b| a.cmp(b));
+        assert_eq!(merged, [0, 1, 4, 4, 4, 5, 9, 12]);
+        assert_eq!(merge_sorted(Vec::new(), vec![3], |a, b| a.cmp(b)), [3]);
+    }
+
+    #[test]
+    fn test_sort_orders() {
+        let names = [
+            ("b.rs", false),
+            ("src", true),
+            ("a.toml", false),
+            ("docs", true),
+        ];
+        let mut order = SortOrder {
+            mode: SortMode::Name,
+            reverse: false,
+            dirs_first: true,
+        };
+        assert_eq!(sorted(order, &names), ["docs", "src", "a.toml", "b.rs"]);
+
+        order.reverse = true;
+        assert_eq!(sorted(order, &names), ["src", "docs", "b.rs", "a.toml"]);
+
+        order.reverse = false;
+        order.dirs_first = false;
+        order.mode = SortMode::Extension;
+        assert_eq!(sorted(order, &names), ["docs", "src", "b.rs", "a.toml"]);
+
+        order.mode = SortMode::Frecency;
+        assert_eq!(sorted(order, &names), ["b.rs", "src", "a.toml", "docs"]);
+
+        assert_eq!(SortMode::Frecency.next(), SortMode::Name);
+        assert_eq!("size".parse(), Ok(SortMode::Size));
+    }
+}
@@ -0,0 +1,161 @@
+use std::fmt;
+use std::ops::Deref;
+
+/// Single-line text field with a cursor, used by the search query and the prompts
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextInput {
+    text: String,
+    /// Byte offset into `text`, always on a char boundary
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Text before and after the cursor
+    pub fn split(&self) -> (&str, &str) {
+        self.text.split_at(self.cursor)
+    }
+
+    pub fn set(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.cursor = self.text.len();
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// Delete the character before the cursor
+    pub fn backspace(&mut self) {
+        if let Some(c) = self.text[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+            self.text.remove(self.cursor);
+        }
+    }
+
+    /// Delete the character under the cursor
+    pub fn delete(&mut self) {
+        if self.cursor < self.text.len() {
+            self.text.remove(self.cursor);
+        }
+    }
+
+    /// Delete the word before the cursor; `/` also ends a word so path segments go one at a time
+    pub fn delete_word(&mut self) {
+        let before = &self.text[..self.cursor];
+        let trimmed = before.trim_end_matches([' ', '/']);
+        let start = trimmed.rfind([' ', '/']).map(|i| i + 1).unwrap_or(0);
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    /// Delete everything before the cursor
+    pub fn delete_to_start(&mut self) {
+        self.text.replace_range(..self.cursor, "");
+        self.cursor = 0;
+    }
+
+    pub fn move_left(&mut self) {
+        if let Some(c) = self.text[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if let Some(c) = self.text[self.cursor..].chars().next() {
+            self.cursor += c.len_utf8();
+        }
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.text.len();
+    }
+}
+
+impl Deref for TextInput {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl fmt::Display for TextInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl PartialEq<&str> for TextInput {
+    fn eq(&self, other: &&str) -> bool {
+        self.text == *other
+    }
+}
+
+impl From<&str> for TextInput {
+    fn from(text: &str) -> Self {
+        let mut input = Self::new();
+        input.set(text);
+        input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_move() {
+        let mut input = TextInput::from("jmp");
+        input.move_left();
+        input.move_left();
+        input.insert('u');
+        assert_eq!(input, "jump");
+        assert_eq!(input.split(), ("ju", "mp"));
+
+        input.move_home();
+        input.delete();
+        input.move_end();
+        input.backspace();
+        assert_eq!(input, "um");
+    }
+
+    #[test]
+    fn test_multibyte_cursor() {
+        let mut input = TextInput::from("café");
+        input.move_left();
+        assert_eq!(input.split(), ("caf", "é"));
+        input.move_right();
+        input.backspace();
+        assert_eq!(input, "caf");
+    }
+
+    #[test]
+    fn test_delete_word_and_to_start() {
+        let mut input = TextInput::from("src/ui/fuzzy");
+        input.delete_word();
+        assert_eq!(input, "src/ui/");
+        input.delete_word();
+        assert_eq!(input, "src/");
+
+        let mut input = TextInput::from("one two three");
+        input.move_left();
+        input.move_left();
+        input.delete_to_start();
+        assert_eq!(input, "ee");
+        assert_eq!(input.split(), ("", "ee"));
+    }
+}
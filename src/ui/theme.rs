@@ -0,0 +1,25 @@
+use ratatui::style::Color;
+
+/// Colors used by the TUI, configurable under `[colors]` in config.toml
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// Key hints and toggle status
+    pub accent: Color,
+    /// Selected directory and input borders
+    pub selection: Color,
+    /// Bookmark star and alias
+    pub bookmark: Color,
+    /// Selected file
+    pub file: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            accent: Color::Cyan,
+            selection: Color::Blue,
+            bookmark: Color::Yellow,
+            file: Color::Yellow,
+        }
+    }
+}